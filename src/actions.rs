@@ -0,0 +1,306 @@
+use std::{fs, sync::Arc};
+
+use gtk4 as gtk;
+use gtk::{*, prelude::*};
+use log::{error, info, warn};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::attempts::Attempts;
+use crate::components::build::{FormFields, Wrapped};
+use crate::config::{Config, KeyboardLayout};
+
+const PASSWD_PATH: &str = "/etc/passwd";
+/// Lowest uid of a regular user account
+const UID_MIN: u32 = 1000;
+/// Highest uid of a regular user account
+const UID_MAX: u32 = 60000;
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    /// Reboot the system
+    Reboot,
+    /// Power off the system
+    Poweroff,
+    /// Run a custom command
+    Run(String),
+    /// Select the runner with the given name
+    Runner(String),
+    /// Select the next runner in the runner dropdown
+    NextRunner,
+    /// Select the previous runner in the runner dropdown
+    PreviousRunner,
+    /// Select the next layout in the keyboard layout dropdown
+    NextKeyboardLayout,
+    /// Select the previous layout in the keyboard layout dropdown
+    PreviousKeyboardLayout,
+    /// Fill the next user of the system into the username field
+    NextUser,
+    /// Fill the previous user of the system into the username field
+    PreviousUser,
+    /// Focus the username field
+    FocusUsername,
+    /// Focus the password field
+    FocusPassword,
+    /// Focus the runner dropdown
+    FocusRunner,
+    /// Reset all form fields and remove their error state
+    ClearForm,
+    /// Submit the login form
+    Submit
+}
+
+/// Convert a human readable key combination (e.g. `Ctrl+Alt+Delete`)
+/// into a gtk accelerator string (e.g. `<Control><Alt>Delete`)
+fn parse_accelerator(keys: &str) -> Option<String> {
+    let parts = keys.split('+').map(str::trim).collect::<Vec<_>>();
+    let (key, modifiers) = parts.split_last()?;
+    if key.is_empty() {
+        return None;
+    }
+
+    let mut accelerator = String::new();
+    for modifier in modifiers {
+        let name = match modifier.to_lowercase().as_str() {
+            "ctrl" | "control" => "<Control>",
+            "alt" => "<Alt>",
+            "shift" => "<Shift>",
+            "super" | "logo" | "win" => "<Super>",
+            "meta" => "<Meta>",
+            _ => return None
+        };
+        accelerator.push_str(name);
+    }
+
+    if key.chars().count() == 1 {
+        accelerator.push_str(&key.to_lowercase());
+    } else {
+        accelerator.push_str(key);
+    }
+    Some(accelerator)
+}
+
+/// Register all configured keybindings on the form window
+pub fn add_keybindings(window: &ApplicationWindow, fields: &FormFields, attempts: &Wrapped<Attempts>, config: &Arc<Config>) {
+    let controller = ShortcutController::new();
+    controller.set_scope(ShortcutScope::Global);
+
+    config.keybindings.iter().for_each(|(keys, action)| {
+        let Some(trigger) = parse_accelerator(keys).and_then(|accel| ShortcutTrigger::parse_string(&accel)) else {
+            warn!("invalid keybinding {keys}, ignoring it");
+            return;
+        };
+
+        let (cf, ca, cc, action) = (fields.clone(), attempts.clone(), config.clone(), action.clone());
+        let callback = CallbackAction::new(move |_, _| {
+            handle_action(&action, &cf, &ca, cc.clone());
+            glib::Propagation::Stop
+        });
+        controller.add_shortcut(Shortcut::new(Some(trigger), Some(callback)));
+    });
+
+    window.add_controller(controller);
+}
+
+pub fn handle_action(action: &Action, fields: &FormFields, attempts: &Wrapped<Attempts>, config: Arc<Config>) {
+    info!("running action {action:?}");
+    match action {
+        Action::Reboot => run_command("systemctl reboot"),
+        Action::Poweroff => run_command("systemctl poweroff"),
+        Action::Run(command) => run_command(command),
+        Action::Runner(name) => {
+            let Some(runner) = config.runners.get(name) else {
+                warn!("no runner named {name} found");
+                return;
+            };
+            with_runner_dropdown(fields, |dropdown| {
+                let Some(model) = dropdown.model() else { return };
+                let position = (0..model.n_items()).find(|&i| {
+                    model.item(i)
+                        .and_downcast::<StringObject>()
+                        .is_some_and(|item| item.string() == runner.display_name)
+                });
+                match position {
+                    Some(position) => dropdown.set_selected(position),
+                    None => warn!("runner {name} is not part of the runner dropdown"),
+                }
+            });
+        },
+        Action::NextRunner => with_runner_dropdown(fields, |dropdown| cycle_dropdown(dropdown, 1)),
+        Action::PreviousRunner => with_runner_dropdown(fields, |dropdown| cycle_dropdown(dropdown, -1)),
+        Action::NextKeyboardLayout => with_keyboard_layout_dropdown(fields, |dropdown| cycle_dropdown(dropdown, 1)),
+        Action::PreviousKeyboardLayout => with_keyboard_layout_dropdown(fields, |dropdown| cycle_dropdown(dropdown, -1)),
+        Action::NextUser => cycle_user(fields, 1),
+        Action::PreviousUser => cycle_user(fields, -1),
+        Action::FocusUsername => {
+            match &fields.username {
+                Some(username) => { username.as_ref().borrow().grab_focus(); },
+                None => warn!("no username field to focus"),
+            }
+        },
+        Action::FocusPassword => {
+            match &fields.password {
+                Some(password) => { password.as_ref().borrow().grab_focus(); },
+                None => warn!("no password field to focus"),
+            }
+        },
+        Action::FocusRunner => with_runner_dropdown(fields, |dropdown| { dropdown.grab_focus(); }),
+        Action::ClearForm => {
+            if let Some(username) = &fields.username {
+                let tmp = username.as_ref().borrow();
+                let entry = tmp.downcast_ref::<Entry>().expect("should be entry");
+                entry.set_text(&config.username.clone().unwrap_or_default());
+                entry.remove_css_class(&config.classes.field_error);
+            }
+            if let Some(password) = &fields.password {
+                let tmp = password.as_ref().borrow();
+                let entry = tmp.downcast_ref::<PasswordEntry>().expect("should be password entry");
+                entry.set_text("");
+                entry.remove_css_class(&config.classes.field_error);
+            }
+
+            focus_form(fields);
+        },
+        Action::Submit => crate::handle_submit(fields, attempts, config),
+    }
+}
+
+/// Focus the username field if it's empty and the password field otherwise
+pub(crate) fn focus_form(fields: &FormFields) {
+    let username_empty = fields.username.as_ref().is_some_and(|username| {
+        let tmp = username.as_ref().borrow();
+        tmp.downcast_ref::<Entry>().expect("should be entry").text().is_empty()
+    });
+    let focus = if username_empty { &fields.username } else { &fields.password };
+    if let Some(widget) = focus {
+        widget.as_ref().borrow().grab_focus();
+    }
+}
+
+/// Split the command like a shell would and spawn it. The command is waited
+/// for on a separate thread, so it neither blocks the greeter nor stays a zombie
+pub(crate) fn run_command(command: &str) {
+    let Some(argv) = shlex::split(command).filter(|argv| !argv.is_empty()) else {
+        error!("received invalid command: {command}");
+        return;
+    };
+
+    match std::process::Command::new(&argv[0]).args(&argv[1..]).spawn() {
+        Ok(mut child) => {
+            info!("spawned command: {argv:?}");
+            std::thread::spawn(move || match child.wait() {
+                Ok(status) if status.success() => info!("command {argv:?} exited with {status}"),
+                Ok(status) => warn!("command {argv:?} exited with {status}"),
+                Err(err) => warn!("unable to wait for command {argv:?}: {err}"),
+            });
+        },
+        Err(err) => error!("unable to spawn command {command}: {err}"),
+    }
+}
+
+/// Switch the layout of the compositor running the greeter by running the switch command
+pub(crate) fn switch_keyboard_layout(layout: &KeyboardLayout) {
+    if let Some(cmd) = &layout.switch_cmd {
+        info!("switching keyboard layout to {}", layout.layout);
+        run_command(cmd);
+    }
+}
+
+fn with_runner_dropdown(fields: &FormFields, f: impl FnOnce(&DropDown)) {
+    match &fields.runner {
+        Some(runner) => {
+            let tmp = runner.as_ref().borrow();
+            f(tmp.downcast_ref::<DropDown>().expect("should be dropdown"))
+        },
+        None => warn!("no runner dropdown found for action"),
+    }
+}
+
+fn with_keyboard_layout_dropdown(fields: &FormFields, f: impl FnOnce(&DropDown)) {
+    match &fields.keyboard_layout {
+        Some(keyboard_layout) => {
+            let tmp = keyboard_layout.as_ref().borrow();
+            f(tmp.downcast_ref::<DropDown>().expect("should be dropdown"))
+        },
+        None => warn!("no keyboard layout dropdown found for action"),
+    }
+}
+
+fn cycle_dropdown(dropdown: &DropDown, step: i64) {
+    let Some(model) = dropdown.model() else { return };
+    let count = model.n_items() as i64;
+    if count == 0 {
+        return;
+    }
+    let next = (dropdown.selected() as i64 + step).rem_euclid(count);
+    dropdown.set_selected(next as u32);
+}
+
+fn cycle_user(fields: &FormFields, step: i64) {
+    let Some(username) = &fields.username else {
+        warn!("no username field found to cycle users");
+        return;
+    };
+
+    let users = login_users();
+    if users.is_empty() {
+        warn!("found no users to cycle through");
+        return;
+    }
+
+    let tmp = username.as_ref().borrow();
+    let entry = tmp.downcast_ref::<Entry>().expect("should be entry");
+    let current = entry.text();
+    let next = match users.iter().position(|user| *user == current.as_str()) {
+        Some(index) => (index as i64 + step).rem_euclid(users.len() as i64) as usize,
+        None if step < 0 => users.len() - 1,
+        None => 0
+    };
+    entry.set_text(&users[next]);
+    entry.set_position(-1);
+}
+
+/// Read the names of all regular user accounts which have a login shell
+fn login_users() -> Vec<String> {
+    let content = match fs::read_to_string(PASSWD_PATH) {
+        Ok(content) => content,
+        Err(err) => {
+            error!("unable to read users from {PASSWD_PATH}: {err}");
+            return Vec::new();
+        }
+    };
+
+    content.lines()
+        .filter_map(|line| {
+            let fields = line.split(':').collect::<Vec<_>>();
+            let (name, uid, shell) = (fields.first()?, fields.get(2)?, fields.get(6)?);
+            let uid = uid.parse::<u32>().ok()?;
+            let has_shell = !shell.ends_with("nologin") && !shell.ends_with("false");
+            ((UID_MIN..UID_MAX).contains(&uid) && has_shell).then(|| name.to_string())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_accelerators() {
+        assert_eq!(parse_accelerator("Escape").as_deref(), Some("Escape"));
+        assert_eq!(parse_accelerator("Ctrl+U").as_deref(), Some("<Control>u"));
+        assert_eq!(parse_accelerator("ctrl + alt + Delete").as_deref(), Some("<Control><Alt>Delete"));
+        assert_eq!(parse_accelerator("Super+Shift+F1").as_deref(), Some("<Super><Shift>F1"));
+        // accelerators which are already written in the gtk syntax are kept
+        assert_eq!(parse_accelerator("<Ctrl>u").as_deref(), Some("<Ctrl>u"));
+    }
+
+    #[test]
+    fn rejected_accelerators() {
+        assert_eq!(parse_accelerator(""), None);
+        assert_eq!(parse_accelerator("Ctrl+"), None);
+        assert_eq!(parse_accelerator("Ctrl++"), None);
+        assert_eq!(parse_accelerator("Hyper+a"), None);
+    }
+}
@@ -0,0 +1,196 @@
+use std::{collections::HashMap, sync::Arc, time::{Duration, Instant}};
+
+use glib::{timeout_add_local, ControlFlow};
+use gtk4 as gtk;
+use gtk::{*, prelude::*};
+use log::info;
+
+use crate::components::build::{FormFields, Wrapped};
+use crate::config::Config;
+
+/// Reason why the login is currently blocked for a username
+#[derive(Clone, Copy, Debug)]
+pub enum Block {
+    /// Delay after a failed attempt which grows with each failure
+    Delay(Instant),
+    /// Cooldown after reaching the maximum number of failed attempts
+    Cooldown(Instant),
+}
+
+impl Block {
+    fn until(&self) -> Instant {
+        match self {
+            Block::Delay(until) | Block::Cooldown(until) => *until
+        }
+    }
+
+    /// Remaining time until the block is lifted
+    pub fn remaining(&self) -> Option<Duration> {
+        self.until()
+            .checked_duration_since(Instant::now())
+            .filter(|remaining| !remaining.is_zero())
+    }
+
+    fn message(&self, remaining: Duration) -> String {
+        let seconds = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+        match self {
+            Block::Delay(_) => format!("Please wait {seconds}s before trying again"),
+            Block::Cooldown(_) => format!("Too many failed attempts, try again in {seconds}s"),
+        }
+    }
+}
+
+#[derive(Default)]
+struct UserAttempts {
+    failures: u32,
+    block: Option<Block>,
+}
+
+/// Failed login attempts which are tracked for each username
+#[derive(Default)]
+pub struct Attempts {
+    users: HashMap<String, UserAttempts>,
+    /// Incremented for every countdown so older countdowns stop updating the form
+    countdown: u64,
+}
+
+impl Attempts {
+    /// Active block of the username if there is any
+    pub fn block(&self, username: &str) -> Option<Block> {
+        self.users.get(username)?.block.filter(|block| block.remaining().is_some())
+    }
+
+    /// Record a failed attempt of the username and return the resulting block
+    pub fn record_failure(&mut self, username: &str, config: &Config) -> Option<Block> {
+        let policy = &config.attempts;
+        let user = self.users.entry(username.to_string()).or_default();
+        user.failures += 1;
+
+        user.block = if policy.max_attempts > 0 && user.failures >= policy.max_attempts {
+            info!("reached maximum failed attempts for {username}, starting cooldown");
+            user.failures = 0;
+            Some(Block::Cooldown(Instant::now() + Duration::from_secs(policy.cooldown)))
+        } else {
+            let delay = policy.delay_after(user.failures);
+            (!delay.is_zero()).then(|| Block::Delay(Instant::now() + delay))
+        };
+        user.block
+    }
+}
+
+/// Show the remaining time of a block in the message area and apply
+/// the throttled class to the form window until the block is lifted
+pub fn show_countdown(block: Block, attempts: &Wrapped<Attempts>, fields: &FormFields, config: &Arc<Config>) {
+    let Some(remaining) = block.remaining() else {
+        return;
+    };
+
+    let generation = {
+        let mut attempts = attempts.borrow_mut();
+        attempts.countdown += 1;
+        attempts.countdown
+    };
+
+    let root = fields.password.as_ref().and_then(|password| password.as_ref().borrow().root());
+    let message = fields.message.as_ref().map(|message| {
+        message.as_ref().borrow().clone().downcast::<Label>().expect("should be label")
+    });
+
+    if let Some(root) = &root {
+        root.add_css_class(&config.classes.throttled);
+    }
+    if let Some(message) = &message {
+        message.set_label(&block.message(remaining));
+    }
+
+    let (ca, cc) = (attempts.clone(), config.clone());
+    timeout_add_local(Duration::from_millis(250), move || {
+        if ca.borrow().countdown != generation {
+            return ControlFlow::Break;
+        }
+
+        match block.remaining() {
+            Some(remaining) => {
+                if let Some(message) = &message {
+                    message.set_label(&block.message(remaining));
+                }
+                ControlFlow::Continue
+            },
+            None => {
+                if let Some(root) = &root {
+                    root.remove_css_class(&cc.classes.throttled);
+                }
+                if let Some(message) = &message {
+                    message.set_label("");
+                }
+                ControlFlow::Break
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AttemptPolicy;
+
+    fn config(attempts: AttemptPolicy) -> Config {
+        let mut config = Config::default();
+        config.attempts = attempts;
+        config
+    }
+
+    fn remaining(block: Option<Block>) -> Duration {
+        block.and_then(|block| block.remaining()).unwrap_or_default()
+    }
+
+    #[test]
+    fn delay_grows_with_each_failure() {
+        let config = config(AttemptPolicy { delay: 10000, max_delay: 30000, ..Default::default() });
+        let mut attempts = Attempts::default();
+
+        let first = attempts.record_failure("user", &config);
+        assert!(matches!(first, Some(Block::Delay(_))));
+        assert!(remaining(first) > Duration::from_secs(9) && remaining(first) <= Duration::from_secs(10));
+        let second = attempts.record_failure("user", &config);
+        assert!(remaining(second) > Duration::from_secs(19));
+        let third = attempts.record_failure("user", &config);
+        let fourth = attempts.record_failure("user", &config);
+        assert!(remaining(third) > Duration::from_secs(29) && remaining(fourth) <= Duration::from_secs(30));
+        assert!(attempts.block("user").is_some());
+    }
+
+    #[test]
+    fn cooldown_after_maximum_attempts() {
+        let config = config(AttemptPolicy { delay: 0, max_attempts: 3, cooldown: 60, ..Default::default() });
+        let mut attempts = Attempts::default();
+
+        assert!(attempts.record_failure("user", &config).is_none());
+        assert!(attempts.record_failure("user", &config).is_none());
+        let block = attempts.record_failure("user", &config);
+        assert!(matches!(block, Some(Block::Cooldown(_))));
+        assert!(remaining(block) > Duration::from_secs(59) && remaining(block) <= Duration::from_secs(60));
+        // the failures start over once the cooldown has been started
+        assert_eq!(attempts.users["user"].failures, 0);
+    }
+
+    #[test]
+    fn usernames_are_tracked_separately() {
+        let config = config(AttemptPolicy { delay: 1000, max_attempts: 2, ..Default::default() });
+        let mut attempts = Attempts::default();
+
+        attempts.record_failure("alice", &config);
+        assert!(matches!(attempts.record_failure("alice", &config), Some(Block::Cooldown(_))));
+        assert!(attempts.block("bob").is_none());
+        assert!(matches!(attempts.record_failure("bob", &config), Some(Block::Delay(_))));
+        assert_eq!(attempts.users["bob"].failures, 1);
+    }
+
+    #[test]
+    fn no_block_without_policy() {
+        let config = config(AttemptPolicy::default());
+        let mut attempts = Attempts::default();
+        (0..10).for_each(|_| assert!(attempts.record_failure("user", &config).is_none()));
+        assert!(attempts.block("user").is_none());
+    }
+}
@@ -0,0 +1,186 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    fs,
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+    sync::OnceLock,
+    time::{SystemTime, UNIX_EPOCH}
+};
+
+use log::{error, warn};
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+use crate::config::{
+    BackgroundFit,
+    BackgroundOptions,
+    ColorStop,
+    Config,
+    DirectoryBackground,
+    Gradient,
+    Monitor,
+    MonitorBackground,
+    RadialShape,
+    StyledBackground
+};
+
+const IMAGE_EXTENSIONS: [&str; 7] = ["png", "jpg", "jpeg", "webp", "gif", "bmp", "svg"];
+
+/// Stylesheet of a background window together with the css classes of
+/// all images of a background directory in the order they should be shown
+pub struct BackgroundCss {
+    pub css: String,
+    pub classes: Vec<String>,
+}
+
+/// Build the stylesheet for the background window of a monitor which
+/// is applied using the given class name
+pub fn build_background(class_name: &str, monitor: &Monitor, config: &Config) -> Option<BackgroundCss> {
+    let background = monitor.background.as_ref()?;
+    let css = match background {
+        MonitorBackground::Color(color) => BackgroundCss {
+            css: format!(".{class_name} {{ background-color: {}; }}", color.css()),
+            classes: Vec::new()
+        },
+        MonitorBackground::Image(path) => BackgroundCss {
+            css: format!(
+                ".{class_name} {{ background-image: {}; {} }}",
+                image_url(&config.resolve_path(path)),
+                options_css(&BackgroundOptions::default())
+            ),
+            classes: Vec::new()
+        },
+        MonitorBackground::Directory(directory) => build_slideshow(class_name, directory, monitor, config),
+        MonitorBackground::Styled(styled) => BackgroundCss {
+            css: format!(".{class_name} {{ {} }}", styled_css(styled, config)),
+            classes: Vec::new()
+        },
+    };
+    Some(css)
+}
+
+fn image_url(path: &Path) -> String {
+    format!("url(\"file://{}\")", path.to_string_lossy())
+}
+
+/// Css declarations for the fit, blur and custom css of a background
+fn options_css(options: &BackgroundOptions) -> String {
+    let fit = match options.fit {
+        BackgroundFit::Cover => "background-size: cover; background-repeat: no-repeat; background-position: center;",
+        BackgroundFit::Contain => "background-size: contain; background-repeat: no-repeat; background-position: center;",
+        BackgroundFit::Tile => "background-size: auto; background-repeat: repeat; background-position: left top;",
+        BackgroundFit::Center => "background-size: auto; background-repeat: no-repeat; background-position: center;",
+    };
+
+    let mut css = String::from(fit);
+    if let Some(blur) = options.blur {
+        css.push_str(&format!(" filter: blur({blur}px);"));
+    }
+    if let Some(custom) = &options.css {
+        css.push(' ');
+        css.push_str(custom.trim());
+        if !custom.trim().ends_with(';') {
+            css.push(';');
+        }
+    }
+    css
+}
+
+fn gradient_css(gradient: &Gradient) -> String {
+    let stops = |stops: &Vec<ColorStop>| stops.iter().map(|stop| match stop {
+        ColorStop::Color(color) => color.css(),
+        ColorStop::Positioned { color, position } => format!("{} {position}%", color.css()),
+    }).collect::<Vec<_>>().join(", ");
+
+    match gradient {
+        Gradient::Linear { angle, stops: s } => format!("linear-gradient({angle}deg, {})", stops(s)),
+        Gradient::Radial { shape, stops: s } => {
+            let shape = match shape {
+                RadialShape::Circle => "circle",
+                RadialShape::Ellipse => "ellipse",
+            };
+            format!("radial-gradient({shape}, {})", stops(s))
+        }
+    }
+}
+
+fn styled_css(styled: &StyledBackground, config: &Config) -> String {
+    let mut css = String::new();
+    if let Some(color) = &styled.color {
+        css.push_str(&format!("background-color: {}; ", color.css()));
+    }
+
+    // the first layer is drawn on top
+    let layers = [
+        styled.gradient.as_ref().map(gradient_css),
+        styled.image.as_ref().map(|path| image_url(&config.resolve_path(path))),
+    ].into_iter().flatten().collect::<Vec<_>>();
+    if !layers.is_empty() {
+        css.push_str(&format!("background-image: {}; ", layers.join(", ")));
+    }
+
+    css.push_str(&options_css(&styled.options));
+    css
+}
+
+/// Seed which is shared by all monitors which don't have a fixed seed
+fn startup_seed() -> u64 {
+    static SEED: OnceLock<u64> = OnceLock::new();
+    *SEED.get_or_init(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos() as u64)
+            .unwrap_or_default()
+    })
+}
+
+/// All images inside a directory sorted by their file name
+fn list_images(directory: &Path) -> Vec<PathBuf> {
+    let entries = match fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(err) => {
+            error!("unable to read background directory {}: {err}", directory.to_string_lossy());
+            return Vec::new();
+        }
+    };
+
+    let mut images = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.is_file() && path.extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        })
+        .collect::<Vec<_>>();
+    images.sort();
+    images
+}
+
+/// Build a css class for each image in the background directory in a shuffled order.
+/// Monitors with a shared selection use the same order while all other monitors get their own
+fn build_slideshow(class_name: &str, background: &DirectoryBackground, monitor: &Monitor, config: &Config) -> BackgroundCss {
+    let mut images = list_images(&config.resolve_path(&background.directory));
+    if images.is_empty() {
+        warn!("found no images in background directory {}", background.directory);
+    }
+
+    let mut seed = background.seed.unwrap_or_else(startup_seed);
+    if !background.shared {
+        let mut hasher = DefaultHasher::new();
+        monitor.output.hash(&mut hasher);
+        seed ^= hasher.finish();
+    }
+    images.shuffle(&mut StdRng::seed_from_u64(seed));
+
+    let mut css = format!(
+        ".{class_name} {{ transition: background-image {}ms ease-in-out; {} }}",
+        background.transition,
+        options_css(&background.options)
+    );
+    let classes = images.iter().enumerate().map(|(index, image)| {
+        let image_class = format!("{class_name}-{index}");
+        css.push_str(&format!("\n.{image_class} {{ background-image: {}; }}", image_url(image)));
+        image_class
+    }).collect();
+
+    BackgroundCss { css, classes }
+}
@@ -1,23 +1,64 @@
 use std::path::Path;
 use dirs::home_dir;
-use clap::Parser;
+use clap::{Parser, Subcommand, ValueEnum};
 
 const CONFIG_PATH: &str = ".config/sali/config.yaml";
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 pub struct Cli {
-    #[arg(long, short, default_value_t = get_default_config_path())]
+    #[arg(long, short, global = true, default_value_t = get_default_config_path())]
     /// Alternative path to a config file
     pub config: String,
 
     #[arg(long, short)]
     /// Start the gtk inspector on application launch
-    pub inspect: bool
+    pub inspect: bool,
+
+    #[command(subcommand)]
+    pub command: Option<Command>
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Print the layout tree after all defaults are applied
+    Layout {
+        #[arg(long, short, value_enum, default_value_t = LayoutFormat::Tree)]
+        /// Format in which the layout tree is printed
+        format: LayoutFormat
+    },
+    /// Write an example config with all fields and their default values
+    Init {
+        #[arg(long, short)]
+        /// Path of the written config, defaults to the config path
+        output: Option<String>,
+
+        #[arg(long, short)]
+        /// Also write a starter styles.scss next to the config
+        styles: bool,
+
+        #[arg(long)]
+        /// Overwrite already existing files
+        force: bool
+    },
+    /// Print the json schema of the config file
+    Schema,
+    /// Check the config file for errors without starting the greeter
+    Validate
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum LayoutFormat {
+    /// Human readable tree with the css classes of each node
+    Tree,
+    /// Yaml representation of the layout
+    Yaml,
+    /// Json representation of the layout
+    Json
 }
 
 fn get_default_config_path() -> String {
     let home_dir = home_dir().unwrap_or_default();
     let path = home_dir.join(Path::new(CONFIG_PATH));
     String::from(path.to_str().unwrap_or_default())
-}
\ No newline at end of file
+}
@@ -0,0 +1,111 @@
+use std::{fs, path::Path};
+
+use gtk4 as gtk;
+use gtk::{gdk, prelude::*};
+use log::{error, info, warn};
+
+const CONFIG_TEMPLATE: &str = include_str!("templates/config.yaml");
+const STYLES_TEMPLATE: &str = include_str!("templates/styles.scss");
+const STYLES_FILE: &str = "styles.scss";
+/// Output which is used when no monitors could be detected
+const FALLBACK_OUTPUT: &str = "DP-1";
+
+/// Write the example config and optionally the starter stylesheet
+/// next to it and return the exit code of the process
+pub fn write_config(output: &String, styles: bool, force: bool) -> i32 {
+    let config_path = Path::new(output);
+    let styles_path = config_path.parent().unwrap_or(Path::new("")).join(STYLES_FILE);
+
+    let existing = [Some(config_path), styles.then_some(styles_path.as_path())]
+        .into_iter()
+        .flatten()
+        .find(|path| path.exists());
+    if let (Some(path), false) = (existing, force) {
+        error!("{} already exists, use --force to overwrite it", path.to_string_lossy());
+        return 1;
+    }
+
+    let config = render_config(&detect_outputs(), styles);
+
+    if let Some(parent) = config_path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        if let Err(err) = fs::create_dir_all(parent) {
+            error!("unable to create config directory {}: {err}", parent.to_string_lossy());
+            return 1;
+        }
+    }
+
+    let mut files = vec![(config_path, config)];
+    if styles {
+        files.push((styles_path.as_path(), String::from(STYLES_TEMPLATE)));
+    }
+    for (path, content) in files {
+        if let Err(err) = fs::write(path, content) {
+            error!("unable to write {}: {err}", path.to_string_lossy());
+            return 1;
+        }
+        info!("wrote {}", path.to_string_lossy());
+    }
+    0
+}
+
+/// Fill the config template with the given outputs, of which the first one
+/// becomes the main monitor
+fn render_config(outputs: &[String], styles: bool) -> String {
+    let monitors = outputs.iter()
+        .map(|output| format!(
+            "  {}:\n    # Hardware output of the monitor\n    output: {output}\n    # Background image, directory or color of the monitor\n    # background: [46, 52, 64]",
+            monitor_name(output)
+        ))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    CONFIG_TEMPLATE
+        .replace("{{monitors}}", &monitors)
+        .replace("{{main_monitor}}", &monitor_name(&outputs[0]))
+        .replace("{{styles}}", if styles { " \"styles.scss\" " } else { "" })
+}
+
+/// Name under which a monitor is added to the config
+fn monitor_name(output: &str) -> String {
+    output.to_lowercase()
+}
+
+/// Outputs of all monitors connected to the default display
+fn detect_outputs() -> Vec<String> {
+    let outputs = gtk::init().ok()
+        .and_then(|_| gdk::Display::default())
+        .map(|display| {
+            let monitors = display.monitors();
+            (0..monitors.n_items())
+                .filter_map(|i| monitors.item(i).and_downcast::<gdk::Monitor>())
+                .filter_map(|monitor| monitor.connector())
+                .map(String::from)
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    if outputs.is_empty() {
+        warn!("unable to detect any monitors, using {FALLBACK_OUTPUT} as placeholder");
+        return vec![String::from(FALLBACK_OUTPUT)];
+    }
+    outputs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn rendered_template_is_valid_config() {
+        let outputs = vec![String::from("DP-1"), String::from("HDMI-A-1")];
+        for styles in [false, true] {
+            let config: Config = serde_yaml::from_str(&render_config(&outputs, styles))
+                .expect("rendered template should be a valid config");
+            assert_eq!(config.main_monitor, "dp-1");
+            assert_eq!(config.monitors.len(), 2);
+            assert_eq!(config.monitors["hdmi-a-1"].output, "HDMI-A-1");
+            assert_eq!(config.styles.len(), usize::from(styles));
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use std::collections::HashSet;
+
+use log::error;
+
+use crate::cli::LayoutFormat;
+use crate::components::Component;
+use crate::config::Config;
+
+/// Print the layout tree of the config in the given format
+pub fn print_layout(config_path: &String, format: LayoutFormat) -> i32 {
+    let config = Config::new(config_path);
+
+    let output = match format {
+        LayoutFormat::Tree => {
+            let mut lines = Vec::new();
+            let mut seen = HashSet::new();
+            format_node(&config.layout, "", "", &mut seen, &mut lines);
+            Ok(lines.join("\n"))
+        },
+        LayoutFormat::Yaml => serde_yaml::to_string(&config.layout).map_err(|err| err.to_string()),
+        LayoutFormat::Json => serde_json::to_string_pretty(&config.layout).map_err(|err| err.to_string()),
+    };
+
+    match output {
+        Ok(output) => {
+            println!("{}", output.trim_end());
+            0
+        },
+        Err(err) => {
+            error!("unable to serialize layout: {err}");
+            1
+        }
+    }
+}
+
+/// Format a node and all of its children. Unique components are marked as active
+/// for the first occurrence and as ignored for all further ones, just like
+/// they are treated when the component tree is built. Components hidden by a
+/// static condition are marked as hidden and don't count as occurrence
+fn format_node(
+    component: &Component,
+    prefix: &str,
+    child_prefix: &str,
+    seen: &mut HashSet<&'static str>,
+    lines: &mut Vec<String>
+) {
+    let mut line = format!("{prefix}{}", component.kind());
+    if !component.classes().is_empty() {
+        line.push_str(&format!(" .{}", component.classes().join(".")));
+    }
+    if let Some(condition) = component.hidden_by() {
+        line.push_str(&format!(" (hidden: {condition})"));
+    } else if component.is_unique() {
+        if seen.insert(component.kind()) {
+            line.push_str(" (active)");
+        } else {
+            line.push_str(" (ignored duplicate)");
+        }
+    }
+    lines.push(line);
+    if component.hidden_by().is_some() {
+        return;
+    }
+
+    let children = component.children();
+    let count = children.len();
+    children.into_iter().enumerate().for_each(|(index, child)| {
+        let last = index + 1 == count;
+        let (branch, indent) = if last { ("└── ", "    ") } else { ("├── ", "│   ") };
+        format_node(
+            child,
+            &format!("{child_prefix}{branch}"),
+            &format!("{child_prefix}{indent}"),
+            seen,
+            lines
+        );
+    });
+}
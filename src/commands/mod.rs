@@ -0,0 +1,18 @@
+use crate::cli::Command;
+
+mod init;
+mod layout;
+mod schema;
+mod validate;
+
+/// Run a subcommand and return the exit code of the process
+pub fn run(command: Command, config_path: &String) -> i32 {
+    match command {
+        Command::Layout { format } => layout::print_layout(config_path, format),
+        Command::Init { output, styles, force } => {
+            init::write_config(output.as_ref().unwrap_or(config_path), styles, force)
+        },
+        Command::Schema => schema::print_schema(),
+        Command::Validate => validate::validate_config(config_path),
+    }
+}
@@ -0,0 +1,65 @@
+use std::collections::BTreeMap;
+
+use log::error;
+use schemars::{gen::SchemaSettings, schema::Schema, JsonSchema};
+
+use crate::components::Component;
+use crate::config::Config;
+
+/// Config files which are merged below the config file
+#[derive(JsonSchema)]
+#[serde(untagged)]
+#[allow(dead_code)]
+enum Include {
+    File(String),
+    Files(Vec<String>)
+}
+
+/// Node which is replaced by an instance of a named template
+#[derive(JsonSchema)]
+#[allow(dead_code)]
+struct UseNode {
+    #[serde(rename = "type")]
+    kind: UseType,
+    /// Name of the template to instantiate
+    template: String,
+    /// Values which replace the `{{name}}` placeholders in the template
+    #[serde(default)]
+    params: BTreeMap<String, serde_json::Value>
+}
+
+#[derive(JsonSchema)]
+#[serde(rename_all = "lowercase")]
+#[allow(dead_code)]
+enum UseType {
+    Use
+}
+
+/// Print the json schema of the config which can be used by
+/// editors to autocomplete and validate the config file
+pub fn print_schema() -> i32 {
+    let mut gen = SchemaSettings::draft07().into_generator();
+    let mut schema = gen.root_schema_for::<Config>();
+
+    // includes and templates are resolved before the config is deserialized,
+    // therefore they are added to the schema separately
+    let properties = &mut schema.schema.object().properties;
+    properties.insert(String::from("include"), gen.subschema_for::<Include>());
+    properties.insert(String::from("templates"), gen.subschema_for::<BTreeMap<String, Component>>());
+    let use_node = gen.subschema_for::<UseNode>();
+    schema.definitions.extend(gen.take_definitions());
+    if let Some(Schema::Object(component)) = schema.definitions.get_mut("Component") {
+        component.subschemas().one_of.get_or_insert_with(Vec::new).push(use_node);
+    }
+
+    match serde_json::to_string_pretty(&schema) {
+        Ok(schema) => {
+            println!("{schema}");
+            0
+        },
+        Err(err) => {
+            error!("unable to serialize config schema: {err}");
+            1
+        }
+    }
+}
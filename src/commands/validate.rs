@@ -0,0 +1,71 @@
+use log::{error, info};
+
+use crate::actions::Action;
+use crate::components::Component;
+use crate::config::Config;
+
+/// Load the config and check it for problems which would
+/// prevent the greeter from working as expected
+pub fn validate_config(config_path: &String) -> i32 {
+    let config = match Config::load(config_path) {
+        Ok(config) => config,
+        Err(err) => {
+            error!("{err}");
+            return 1;
+        }
+    };
+
+    let problems = find_problems(&config);
+    if problems.is_empty() {
+        info!("config file {config_path} is valid");
+        0
+    } else {
+        problems.iter().for_each(|problem| error!("{problem}"));
+        1
+    }
+}
+
+fn find_problems(config: &Config) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    if !config.monitors.contains_key(&config.main_monitor) {
+        problems.push(format!("main monitor {} is not part of the monitors", config.main_monitor));
+    }
+    if let Some(runner) = config.default_runner.as_ref().filter(|name| !config.runners.contains_key(*name)) {
+        problems.push(format!("default runner {runner} is not part of the runners"));
+    }
+    if let Some(layout) = config.default_keyboard_layout.as_ref().filter(|name| !config.keyboard_layouts.contains_key(*name)) {
+        problems.push(format!("default keyboard layout {layout} is not part of the keyboard layouts"));
+    }
+    config.keybindings.iter().for_each(|(keys, action)| {
+        if let Action::Runner(name) = action {
+            if !config.runners.contains_key(name) {
+                problems.push(format!("keybinding {keys} selects unknown runner {name}"));
+            }
+        }
+    });
+
+    let mut kinds = Vec::new();
+    collect_kinds(&config.layout, &mut kinds);
+    if !kinds.contains(&"password") {
+        problems.push(String::from("no password component is specified"));
+    }
+    if !kinds.contains(&"runner") && config.default_runner.is_none() {
+        problems.push(String::from("neither a runner component nor a default runner is specified"));
+    }
+    if !kinds.contains(&"username") && config.username.is_none() {
+        problems.push(String::from("neither a username component nor a default username is specified"));
+    }
+
+    problems
+}
+
+/// Kinds of all components which are built, components hidden
+/// by a static condition are skipped together with their children
+fn collect_kinds(component: &Component, kinds: &mut Vec<&'static str>) {
+    if component.hidden_by().is_some() {
+        return;
+    }
+    kinds.push(component.kind());
+    component.children().into_iter().for_each(|child| collect_kinds(child, kinds));
+}
@@ -1,54 +1,137 @@
-use std::{cell::RefCell, rc::Rc, sync::Arc, time::Duration};
+use std::{cell::{Cell, RefCell}, collections::HashMap, path::Path, rc::Rc, sync::Arc, time::Duration};
 
-use chrono::Local;
+use chrono::{Local, Timelike};
 use glib::{timeout_add_local, ControlFlow};
 use gtk4 as gtk;
 use gtk::{*, prelude::*};
-use log::warn;
+use log::{info, warn};
 
+use crate::actions::{run_command, switch_keyboard_layout};
 use crate::config::Config;
 
-use super::Component;
+use super::{status, Component};
+use super::condition::Condition;
 
 pub type Wrapped<T> = Rc<RefCell<T>>;
 
+/// Css classes of all charging states a battery can report
+const BATTERY_STATUS_CLASSES: [&str; 5] = ["charging", "discharging", "full", "not-charging", "unknown"];
+
+/// Unique form field widgets which are found while building the component tree
+#[derive(Default, Clone)]
+pub struct FormFields {
+    pub username: Option<Wrapped<Widget>>,
+    pub password: Option<Wrapped<Widget>>,
+    pub runner: Option<Wrapped<Widget>>,
+    pub keyboard_layout: Option<Wrapped<Widget>>,
+    pub message: Option<Wrapped<Widget>>,
+    /// Whether the username field should be reset after a failed login attempt
+    pub clear_username_on_error: bool,
+    /// Components whose visibility depends on the state of the form
+    pub conditions: Vec<(Condition, Conditional)>,
+    /// Pending timeout which removes the error class from the form fields
+    pub error_timeout: Wrapped<Option<glib::SourceId>>,
+}
+
+/// Wrapper of a component with a live condition. It's only visible while the
+/// condition is met and the component itself is visible, so a component which
+/// hides itself (e.g. a battery without battery) isn't shown by its condition
+#[derive(Clone)]
+pub struct Conditional {
+    wrapper: Box,
+    met: Rc<Cell<bool>>,
+}
+
+impl Conditional {
+    fn new(child: &Widget) -> Self {
+        let wrapper = Box::builder()
+            .css_classes(vec![String::from("conditional")])
+            .build();
+        wrapper.append(child);
+        let conditional = Self { wrapper, met: Rc::new(Cell::new(true)) };
+
+        let cc = conditional.clone();
+        child.connect_visible_notify(move |child| cc.update(child));
+        conditional
+    }
+
+    /// Show or hide the component depending on whether its condition is met
+    pub fn set_met(&self, met: bool) {
+        self.met.set(met);
+        if let Some(child) = self.wrapper.first_child() {
+            self.update(&child);
+        }
+    }
+
+    fn update(&self, child: &Widget) {
+        self.wrapper.set_visible(self.met.get() && child.is_visible());
+    }
+}
+
 pub fn build_component_tree(
     component: Component,
-    username: &mut Option<Wrapped<Widget>>,
-    password: &mut Option<Wrapped<Widget>>,
-    runner: &mut Option<Wrapped<Widget>>,
+    fields: &mut FormFields,
+    config: &Arc<Config>
+) -> Option<Wrapped<Widget>> {
+    let properties = component.properties().clone();
+    // static conditions never change, so hidden components aren't built at all
+    if let Some(condition) = component.hidden_by() {
+        info!("skipping {} component, condition {condition} isn't met", component.kind());
+        return None;
+    }
+
+    let widget = build_component(component, fields, config)?;
+    // components with a live condition are placed inside a wrapper, as they may
+    // also change their own visibility which the condition must not overwrite
+    let widget = match properties.visible_if.clone().filter(Condition::is_live) {
+        Some(condition) => {
+            let conditional = Conditional::new(&widget.as_ref().borrow());
+            let wrapper = conditional.wrapper.clone().upcast::<Widget>();
+            fields.conditions.push((condition, conditional));
+            Rc::new(RefCell::new(wrapper))
+        },
+        None => widget
+    };
+    apply_properties(&widget.as_ref().borrow(), properties);
+    Some(widget)
+}
+
+fn build_component(
+    component: Component,
+    fields: &mut FormFields,
     config: &Arc<Config>
 ) -> Option<Wrapped<Widget>> {
     match component {
         super::Component::Username(field) => {
-            match username {
+            match fields.username {
                 Some(_) => {
                     warn!("received other username node, ignoring lower level node");
                     None
                 },
                 None => {
+                    fields.clear_username_on_error = field.clear_on_error;
                     let entry = build_username_field(field, config.username.clone());
                     let widget = Rc::new(RefCell::new(entry.upcast::<Widget>()));
-                    *username = Some(widget.clone());
+                    fields.username = Some(widget.clone());
                     return Some(widget)
                 }
             }
         },
         super::Component::Password(field) => {
-            match password {
+            match fields.password {
                 Some(_) => {
                     warn!("received other password node, ignoring lower level node");
                     None
                 },
                 None => {
                     let entry = Rc::new(RefCell::new(build_password_field(field).upcast::<Widget>()));
-                    *password = Some(entry.clone());
+                    fields.password = Some(entry.clone());
                     return Some(entry)
                 }
             }
         },
         super::Component::Runner(field) => {
-            match runner {
+            match fields.runner {
                 Some(_) => {
                     warn!("received other runner node, ignoring lower level node");
                     None
@@ -57,14 +140,42 @@ pub fn build_component_tree(
                     let options = config.runners.values().map(|r| r.display_name.as_str()).collect::<Vec<_>>();
                     let dropdown = build_runner_field(field, options, config.default_runner.clone());
                     let widget = Rc::new(RefCell::new(dropdown.upcast::<Widget>()));
-                    *runner = Some(widget.clone());
+                    fields.runner = Some(widget.clone());
+                    return Some(widget)
+                }
+            }
+        },
+        super::Component::KeyboardLayout(field) => {
+            match fields.keyboard_layout {
+                Some(_) => {
+                    warn!("received other keyboard layout node, ignoring lower level node");
+                    None
+                },
+                None => {
+                    let dropdown = build_keyboard_layout_field(field, config);
+                    let widget = Rc::new(RefCell::new(dropdown.upcast::<Widget>()));
+                    fields.keyboard_layout = Some(widget.clone());
+                    return Some(widget)
+                }
+            }
+        },
+        super::Component::Message(message) => {
+            match fields.message {
+                Some(_) => {
+                    warn!("received other message node, ignoring lower level node");
+                    None
+                },
+                None => {
+                    let label = build_message(message);
+                    let widget = Rc::new(RefCell::new(label.upcast::<Widget>()));
+                    fields.message = Some(widget.clone());
                     return Some(widget)
                 }
             }
         },
         super::Component::Box(bx) => {
             let children = bx.children.clone().into_iter()
-                .filter_map(|c| build_component_tree(c, username, password, runner, config))
+                .filter_map(|c| build_component_tree(c, fields, config))
                 .collect::<Vec<_>>();
 
             let built = build_box(bx);
@@ -75,6 +186,87 @@ pub fn build_component_tree(
 
             Some(Rc::new(RefCell::new(built.upcast::<Widget>())))
         },
+        super::Component::Grid(grid) => {
+            let built = build_grid(&grid);
+            grid.children.into_iter().for_each(|child| {
+                if let Some(widget) = build_component_tree(child.component, fields, config) {
+                    let widget = widget.as_ref().borrow();
+                    built.attach(widget.as_ref() as &Widget, child.column, child.row, child.column_span, child.row_span);
+                }
+            });
+
+            Some(Rc::new(RefCell::new(built.upcast::<Widget>())))
+        },
+        super::Component::Overlay(overlay) => {
+            let built = build_overlay(&overlay);
+            if let Some(child) = overlay.child {
+                if let Some(widget) = build_component_tree(*child, fields, config) {
+                    built.set_child(Some(widget.as_ref().borrow().as_ref() as &Widget));
+                }
+            }
+            overlay.overlays.into_iter()
+                .filter_map(|c| build_component_tree(c, fields, config))
+                .for_each(|child| {
+                    let widget = child.as_ref().borrow();
+                    built.add_overlay(widget.as_ref() as &Widget)
+                });
+
+            Some(Rc::new(RefCell::new(built.upcast::<Widget>())))
+        },
+        super::Component::CenterBox(center_box) => {
+            let built = build_center_box(&center_box);
+            let mut build_slot = |slot: Option<std::boxed::Box<super::Component>>| {
+                slot.and_then(|c| build_component_tree(*c, fields, config))
+            };
+            // the slots are built in reading order to keep the unique form fields deterministic
+            if let Some(widget) = build_slot(center_box.start) {
+                built.set_start_widget(Some(widget.as_ref().borrow().as_ref() as &Widget));
+            }
+            if let Some(widget) = build_slot(center_box.center) {
+                built.set_center_widget(Some(widget.as_ref().borrow().as_ref() as &Widget));
+            }
+            if let Some(widget) = build_slot(center_box.end) {
+                built.set_end_widget(Some(widget.as_ref().borrow().as_ref() as &Widget));
+            }
+
+            Some(Rc::new(RefCell::new(built.upcast::<Widget>())))
+        },
+        super::Component::Stack(stack) => {
+            let built = build_stack(&stack);
+            stack.pages.into_iter().for_each(|page| {
+                if let Some(widget) = build_component_tree(page.component, fields, config) {
+                    let widget = widget.as_ref().borrow();
+                    match &page.title {
+                        Some(title) => built.add_titled(widget.as_ref() as &Widget, Some(&page.name), title),
+                        None => built.add_named(widget.as_ref() as &Widget, Some(&page.name)),
+                    };
+                }
+            });
+            if let Some(visible) = &stack.visible {
+                if built.child_by_name(visible).is_some() {
+                    built.set_visible_child_name(visible);
+                } else {
+                    warn!("stack has no page named {visible}, showing first page");
+                }
+            }
+
+            if stack.switcher {
+                let switcher = StackSwitcher::builder()
+                    .stack(&built)
+                    .halign(gtk::Align::Center)
+                    .build();
+                let container = Box::builder()
+                    .orientation(gtk::Orientation::Vertical)
+                    .hexpand(stack.properties.hexpand.unwrap_or(true))
+                    .vexpand(stack.properties.vexpand.unwrap_or(true))
+                    .build();
+                container.append(&switcher);
+                container.append(&built);
+                Some(Rc::new(RefCell::new(container.upcast::<Widget>())))
+            } else {
+                Some(Rc::new(RefCell::new(built.upcast::<Widget>())))
+            }
+        },
         super::Component::DateTime(datetime) => {
             let widget = build_datetime(datetime).upcast::<Widget>();
             Some(Rc::new(RefCell::new(widget)))
@@ -86,10 +278,61 @@ pub fn build_component_tree(
         super::Component::Button(button) => {
             let widget = build_button(button).upcast::<Widget>();
             Some(Rc::new(RefCell::new(widget)))
+        },
+        super::Component::LockKeys(lock_keys) => {
+            let widget = build_lock_keys(lock_keys).upcast::<Widget>();
+            Some(Rc::new(RefCell::new(widget)))
+        },
+        super::Component::Battery(battery) => {
+            let widget = build_battery(battery).upcast::<Widget>();
+            Some(Rc::new(RefCell::new(widget)))
+        },
+        super::Component::Network(network) => {
+            let widget = build_network(network).upcast::<Widget>();
+            Some(Rc::new(RefCell::new(widget)))
+        },
+        super::Component::SysInfo(sysinfo) => {
+            let widget = build_sysinfo(sysinfo).upcast::<Widget>();
+            Some(Rc::new(RefCell::new(widget)))
+        },
+        super::Component::Calendar(calendar) => {
+            let widget = build_calendar(calendar).upcast::<Widget>();
+            Some(Rc::new(RefCell::new(widget)))
+        },
+        super::Component::Clock(clock) => {
+            let widget = build_clock(clock).upcast::<Widget>();
+            Some(Rc::new(RefCell::new(widget)))
         }
     }
 }
 
+fn apply_properties(widget: &Widget, properties: super::WidgetProperties) {
+    if let Some(halign) = properties.halign {
+        widget.set_halign(halign.into());
+    }
+    if let Some(valign) = properties.valign {
+        widget.set_valign(valign.into());
+    }
+    if let Some(hexpand) = properties.hexpand {
+        widget.set_hexpand(hexpand);
+    }
+    if let Some(vexpand) = properties.vexpand {
+        widget.set_vexpand(vexpand);
+    }
+    if let Some(margin) = properties.margin_top.or(properties.margin) {
+        widget.set_margin_top(margin);
+    }
+    if let Some(margin) = properties.margin_bottom.or(properties.margin) {
+        widget.set_margin_bottom(margin);
+    }
+    if let Some(margin) = properties.margin_start.or(properties.margin) {
+        widget.set_margin_start(margin);
+    }
+    if let Some(margin) = properties.margin_end.or(properties.margin) {
+        widget.set_margin_end(margin);
+    }
+}
+
 fn build_username_field(field: super::UsernameComponent, default_username: Option<String>) -> Entry {
     Entry::builder()
         .css_classes(field.classes)
@@ -98,12 +341,31 @@ fn build_username_field(field: super::UsernameComponent, default_username: Optio
         .build()
 }
 
-fn build_password_field(field: super::PasswordComponent) -> Entry {
-    Entry::builder()
+fn build_password_field(field: super::PasswordComponent) -> PasswordEntry {
+    let entry = PasswordEntry::builder()
         .css_classes(field.classes)
         .placeholder_text(field.placeholder)
-        .visibility(false)
-        .build()
+        .show_peek_icon(field.show_peek_icon)
+        .build();
+
+    if let Some(text) = entry.delegate().and_downcast::<Text>() {
+        if let Some(invisible_char) = field.invisible_char {
+            text.set_invisible_char(invisible_char);
+        }
+        // the password should never leave the field, even when it's revealed using the peek icon
+        ["copy-clipboard", "cut-clipboard"].into_iter().for_each(|signal| {
+            text.connect_local(signal, false, move |values| {
+                if let Some(Ok(text)) = values.first().map(|value| value.get::<Text>()) {
+                    text.stop_signal_emission_by_name(signal);
+                }
+                None
+            });
+        });
+    } else {
+        warn!("unable to access text of password field");
+    }
+
+    entry
 }
 
 fn build_runner_field(field: super::RunnerComponent, runners: Vec<&str>, default_runner: Option<String>) -> DropDown {
@@ -123,31 +385,112 @@ fn build_runner_field(field: super::RunnerComponent, runners: Vec<&str>, default
         .build()
 }
 
+fn build_keyboard_layout_field(field: super::KeyboardLayoutComponent, config: &Arc<Config>) -> DropDown {
+    let layouts = config.keyboard_layouts.values().map(|l| l.display_name.as_str()).collect::<Vec<_>>();
+    let model = StringList::new(layouts.as_slice());
+    let selected = config.default_keyboard_layout.as_ref()
+        .and_then(|name| config.keyboard_layouts.keys().position(|key| key == name))
+        .unwrap_or(0) as u32;
+
+    let dropdown = DropDown::builder()
+        .css_classes(field.classes)
+        .model(&model)
+        .selected(selected)
+        .build();
+
+    let cloned_config = config.clone();
+    dropdown.connect_selected_notify(move |dropdown| {
+        let layout = dropdown.selected_item().and_downcast::<StringObject>().and_then(|selected| {
+            cloned_config.keyboard_layouts.values().find(|l| l.display_name == selected.string())
+        });
+        if let Some(layout) = layout {
+            switch_keyboard_layout(layout);
+        }
+    });
+
+    dropdown
+}
+
 fn build_box(bx: super::BoxComponent) -> Box {
     Box::builder()
         .css_classes(bx.classes)
         .orientation(bx.orientation.into())
-        .halign(bx.halign.into())
-        .valign(bx.valign.into())
-        .hexpand(bx.hexpand)
-        .vexpand(bx.vexpand)
+        .halign(bx.properties.halign.unwrap_or(super::Align::Center).into())
+        .valign(bx.properties.valign.unwrap_or(super::Align::Center).into())
+        .hexpand(bx.properties.hexpand.unwrap_or(true))
+        .vexpand(bx.properties.vexpand.unwrap_or(true))
         .width_request(bx.width)
+        .height_request(bx.height)
+        .homogeneous(bx.homogeneous)
         .spacing(bx.spacing)
         .build()
 }
 
+fn build_grid(grid: &super::GridComponent) -> Grid {
+    Grid::builder()
+        .css_classes(grid.classes.clone())
+        .halign(grid.properties.halign.clone().unwrap_or(super::Align::Fill).into())
+        .valign(grid.properties.valign.clone().unwrap_or(super::Align::Fill).into())
+        .hexpand(grid.properties.hexpand.unwrap_or(true))
+        .vexpand(grid.properties.vexpand.unwrap_or(true))
+        .row_spacing(grid.row_spacing)
+        .column_spacing(grid.column_spacing)
+        .row_homogeneous(grid.row_homogeneous)
+        .column_homogeneous(grid.column_homogeneous)
+        .build()
+}
+
+fn build_overlay(overlay: &super::OverlayComponent) -> Overlay {
+    Overlay::builder()
+        .css_classes(overlay.classes.clone())
+        .hexpand(overlay.properties.hexpand.unwrap_or(true))
+        .vexpand(overlay.properties.vexpand.unwrap_or(true))
+        .build()
+}
+
+fn build_center_box(center_box: &super::CenterBoxComponent) -> CenterBox {
+    CenterBox::builder()
+        .css_classes(center_box.classes.clone())
+        .orientation(center_box.orientation.clone().into())
+        .halign(center_box.properties.halign.clone().unwrap_or(super::Align::Fill).into())
+        .valign(center_box.properties.valign.clone().unwrap_or(super::Align::Fill).into())
+        .hexpand(center_box.properties.hexpand.unwrap_or(true))
+        .vexpand(center_box.properties.vexpand.unwrap_or(true))
+        .build()
+}
+
+fn build_stack(stack: &super::StackComponent) -> Stack {
+    Stack::builder()
+        .css_classes(stack.classes.clone())
+        .hexpand(stack.properties.hexpand.unwrap_or(true))
+        .vexpand(stack.properties.vexpand.unwrap_or(true))
+        .transition_type(stack.transition.clone().into())
+        .transition_duration(stack.transition_duration)
+        .build()
+}
+
+/// Update the widget right away and then after each interval until the widget is destroyed
+fn update_every<W: IsA<Widget> + 'static>(widget: &W, interval: u64, update: impl Fn(&W) + 'static) {
+    update(widget);
+
+    let weak = widget.downgrade();
+    timeout_add_local(Duration::from_millis(interval), move || {
+        let Some(widget) = weak.upgrade() else {
+            return ControlFlow::Break;
+        };
+        update(&widget);
+        ControlFlow::Continue
+    });
+}
+
 fn build_datetime(datetime: super::DateTimeComponent) -> Label {
-    let current = format!("{}", Local::now().format(datetime.format.as_str()));
     let label = Label::builder()
         .css_classes(datetime.classes)
-        .label(current)
         .build();
 
-    let cloned_label = label.clone();
-    timeout_add_local(Duration::from_millis(datetime.interval), move || {
+    update_every(&label, datetime.interval, move |label| {
         let formatted = format!("{}", Local::now().format(datetime.format.as_str()));
-        cloned_label.set_label(formatted.as_str());
-        ControlFlow::Continue
+        label.set_label(formatted.as_str());
     });
     label
 }
@@ -159,31 +502,245 @@ fn build_label(label: super::LabelComponent) -> Label {
         .build()
 }
 
+fn build_message(message: super::MessageComponent) -> Label {
+    Label::builder()
+        .css_classes(message.classes)
+        .wrap(true)
+        .build()
+}
+
 fn build_button(button: super::ButtonComponent) -> Button {
     let btn = Button::builder()
         .css_classes(button.classes)
         .label(button.label)
         .build();
 
-    let cloned_command = button.command.clone();
-    btn.connect_clicked(move |_| {
-        if let Some(argv) = shlex::split(&cloned_command) {
-            let mut command = std::process::Command::new(&argv[0]);
-            log::info!("running command: {argv:?}");
-            command.args(&argv[1..]);
-            match command.spawn() {
-                Ok(mut handle) => {
-                    match handle.wait() {
-                        Ok(res) => log::info!("button command succeeded with exit status {res}"),
-                        Err(err) => log::warn!("button command failed: {err}"),
-                    }
-                },
-                Err(err) => log::error!("unable to spawn child process: {err}"),
-            }
-        } else {
-            log::error!("received invalid button command: {}", cloned_command);
+    let command = button.command.clone();
+    btn.connect_clicked(move |_| run_command(&command));
+
+    btn
+}
+
+fn build_lock_keys(lock_keys: super::LockKeysComponent) -> Box {
+    let container = Box::builder()
+        .css_classes(lock_keys.classes)
+        .orientation(gtk::Orientation::Horizontal)
+        .spacing(lock_keys.spacing)
+        .build();
+
+    let Some(keyboard) = gdk::Display::default().and_then(|d| d.default_seat()).and_then(|s| s.keyboard()) else {
+        warn!("found no keyboard device to display lock key state");
+        return container;
+    };
+
+    if let Some(text) = lock_keys.caps_lock {
+        let label = Label::builder()
+            .css_classes(vec![String::from("label"), String::from("caps-lock")])
+            .label(text)
+            .visible(keyboard.is_caps_locked())
+            .build();
+        container.append(&label);
+        let cc = container.clone();
+        keyboard.connect_caps_lock_state_notify(move |device| {
+            label.set_visible(device.is_caps_locked());
+            update_lock_keys_visibility(&cc);
+        });
+    }
+
+    if let Some(text) = lock_keys.num_lock {
+        let label = Label::builder()
+            .css_classes(vec![String::from("label"), String::from("num-lock")])
+            .label(text)
+            .visible(keyboard.is_num_locked())
+            .build();
+        container.append(&label);
+        let cc = container.clone();
+        keyboard.connect_num_lock_state_notify(move |device| {
+            label.set_visible(device.is_num_locked());
+            update_lock_keys_visibility(&cc);
+        });
+    }
+
+    update_lock_keys_visibility(&container);
+    container
+}
+
+/// Hide the lock keys container while none of its labels are visible
+/// so that it doesn't take up any space or spacing in the layout
+fn update_lock_keys_visibility(container: &Box) {
+    let mut child = container.first_child();
+    let mut visible = false;
+    while let Some(widget) = child {
+        visible |= widget.is_visible();
+        child = widget.next_sibling();
+    }
+    container.set_visible(visible);
+}
+
+fn build_battery(battery: super::BatteryComponent) -> Label {
+    let label = Label::builder()
+        .css_classes(battery.classes.clone())
+        .build();
+
+    let interval = battery.interval;
+    update_every(&label, interval, move |label| update_battery(label, &battery));
+    label
+}
+
+fn update_battery(label: &Label, battery: &super::BatteryComponent) {
+    let Some(state) = status::read_battery(Path::new(&battery.path), battery.supply.as_deref()) else {
+        // devices without a battery simply don't show the label
+        label.set_visible(false);
+        return;
+    };
+
+    let text = status::format_placeholders(&battery.format, |name| match name {
+        "capacity" => Some(state.capacity.to_string()),
+        "status" => Some(state.status.clone()),
+        "name" => Some(state.name.clone()),
+        _ => None
+    });
+    label.set_label(&text);
+    label.set_visible(true);
+
+    BATTERY_STATUS_CLASSES.iter().for_each(|class| label.remove_css_class(class));
+    label.add_css_class(&state.status_class());
+    if state.capacity <= battery.low {
+        label.add_css_class("low");
+    } else {
+        label.remove_css_class("low");
+    }
+}
+
+fn build_network(network: super::NetworkComponent) -> Label {
+    let label = Label::builder()
+        .css_classes(network.classes.clone())
+        .build();
+
+    let interval = network.interval;
+    update_every(&label, interval, move |label| update_network(label, &network));
+    label
+}
+
+fn update_network(label: &Label, network: &super::NetworkComponent) {
+    let state = status::read_network(Path::new(&network.path), network.interface.as_deref());
+    let online = state.as_ref().is_some_and(status::NetworkState::is_online);
+
+    let format = if online { &network.format } else { &network.format_offline };
+    let text = status::format_placeholders(format, |name| match name {
+        "interface" => Some(state.as_ref().map(|state| state.interface.clone()).unwrap_or_default()),
+        "address" => Some(state.as_ref().and_then(|state| state.address).map(|address| address.to_string()).unwrap_or_default()),
+        "state" => Some(state.as_ref().map(|state| state.state.clone()).unwrap_or_else(|| String::from("down"))),
+        _ => None
+    });
+    label.set_label(&text);
+
+    let (add, remove) = if online { ("online", "offline") } else { ("offline", "online") };
+    label.remove_css_class(remove);
+    label.add_css_class(add);
+}
+
+fn build_sysinfo(sysinfo: super::SysInfoComponent) -> Label {
+    // fields which never change are only read once
+    let names = status::placeholder_names(&sysinfo.format);
+    let fixed = names.iter()
+        .filter(|name| !status::is_changing_sysinfo(name))
+        .filter_map(|name| status::read_sysinfo(name).map(|value| (name.clone(), value)))
+        .collect::<HashMap<_, _>>();
+    let render = move |format: &str| status::format_placeholders(format, |name| {
+        fixed.get(name).cloned().or_else(|| status::read_sysinfo(name))
+    });
+
+    let label = Label::builder()
+        .css_classes(sysinfo.classes)
+        .build();
+
+    if names.iter().any(|name| status::is_changing_sysinfo(name)) {
+        update_every(&label, sysinfo.interval, move |label| label.set_label(&render(&sysinfo.format)));
+    } else {
+        label.set_label(&render(&sysinfo.format));
+    }
+    label
+}
+
+fn build_calendar(calendar: super::CalendarComponent) -> Calendar {
+    // the calendar only displays the current day and can't be used to pick a date
+    let built = Calendar::builder()
+        .css_classes(calendar.classes)
+        .show_heading(calendar.show_heading)
+        .show_day_names(calendar.show_day_names)
+        .show_week_numbers(calendar.show_week_numbers)
+        .can_target(false)
+        .focusable(false)
+        .build();
+
+    update_every(&built, calendar.interval, |calendar| {
+        match glib::DateTime::now_local() {
+            Ok(today) => calendar.select_day(&today),
+            Err(err) => warn!("unable to get current date for calendar: {err}"),
         }
     });
+    built
+}
 
-    btn
-}
\ No newline at end of file
+fn build_clock(clock: super::ClockComponent) -> DrawingArea {
+    let area = DrawingArea::builder()
+        .css_classes(clock.classes.clone())
+        .content_width(clock.size)
+        .content_height(clock.size)
+        .build();
+
+    let interval = clock.interval;
+    area.set_draw_func(move |area, cr, width, height| draw_clock(area, cr, width, height, &clock));
+    update_every(&area, interval, |area| area.queue_draw());
+    area
+}
+
+/// Color defined in the stylesheets using `@define-color` or the text color of the widget
+fn clock_color(area: &DrawingArea, name: &str) -> gdk::RGBA {
+    let context = area.style_context();
+    context.lookup_color(name).unwrap_or_else(|| context.color())
+}
+
+fn draw_clock(area: &DrawingArea, cr: &cairo::Context, width: i32, height: i32, clock: &super::ClockComponent) {
+    let radius = f64::from(width.min(height)) / 2.0;
+    cr.translate(f64::from(width) / 2.0, f64::from(height) / 2.0);
+    cr.set_line_cap(cairo::LineCap::Round);
+
+    // angles start at 12 o'clock and go clockwise
+    let line = |angle: f64, from: f64, to: f64, line_width: f64, color: gdk::RGBA| {
+        let (x, y) = (angle.sin(), -angle.cos());
+        cr.set_source_rgba(color.red().into(), color.green().into(), color.blue().into(), color.alpha().into());
+        cr.set_line_width(line_width);
+        cr.move_to(x * from, y * from);
+        cr.line_to(x * to, y * to);
+        if let Err(err) = cr.stroke() {
+            warn!("unable to draw clock: {err}");
+        }
+    };
+
+    let marks = match clock.ticks {
+        super::ClockTicks::None => 0,
+        super::ClockTicks::Hours => 12,
+        super::ClockTicks::Minutes => 60,
+    };
+    let tick_color = clock_color(area, "clock_ticks");
+    (0..marks).for_each(|mark| {
+        let is_hour = mark % (marks / 12) == 0;
+        let length = if is_hour { 0.1 } else { 0.05 };
+        let angle = f64::from(mark) * std::f64::consts::TAU / f64::from(marks);
+        line(angle, radius * (0.95 - length), radius * 0.95, if is_hour { 2.0 } else { 1.0 }, tick_color);
+    });
+
+    let now = Local::now();
+    let seconds = f64::from(now.second());
+    let minutes = f64::from(now.minute()) + seconds / 60.0;
+    let hours = f64::from(now.hour() % 12) + minutes / 60.0;
+    let tau = std::f64::consts::TAU;
+
+    line(hours / 12.0 * tau, 0.0, radius * 0.5, clock.hand_width, clock_color(area, "clock_hour_hand"));
+    line(minutes / 60.0 * tau, 0.0, radius * 0.75, clock.hand_width, clock_color(area, "clock_minute_hand"));
+    if clock.second_hand {
+        line(seconds / 60.0 * tau, 0.0, radius * 0.85, clock.hand_width / 2.0, clock_color(area, "clock_second_hand"));
+    }
+}
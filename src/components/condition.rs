@@ -0,0 +1,377 @@
+use std::{fmt, path::Path, sync::Arc};
+
+use gtk4 as gtk;
+use gtk::{prelude::*, DropDown, Entry, StringObject};
+use schemars::{gen::SchemaGenerator, schema::Schema, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{hostname, Config};
+
+use super::build::FormFields;
+
+/// Condition which decides whether a component is visible
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(try_from = "ConditionValue", into = "ConditionValue")]
+pub enum Condition {
+    /// Username field contains a username
+    UserSelected,
+    /// Value of the subject compared to a fixed value
+    Compare {
+        subject: Subject,
+        operator: Operator,
+        value: String
+    },
+    /// Path exists on the system
+    FileExists(String),
+    /// Environment variable is set and not empty
+    Env(String),
+    /// Negation of another condition
+    Not(Box<Condition>)
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Subject {
+    /// Name of the selected runner
+    Runner,
+    /// Text of the username field
+    Username,
+    /// Hostname of the system
+    Hostname
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Operator {
+    Equals,
+    NotEquals,
+    /// Glob pattern where `*` matches any text and `?` any single character
+    Matches
+}
+
+/// State of the login form against which live conditions are evaluated
+#[derive(Default)]
+pub struct FormState {
+    /// Name of the selected runner
+    pub runner: Option<String>,
+    /// Text of the username field
+    pub username: String,
+}
+
+impl Condition {
+    /// Boolean whether the condition depends on the state of the login form
+    /// and has to be re-evaluated when the form changes
+    pub fn is_live(&self) -> bool {
+        match self {
+            Condition::UserSelected => true,
+            Condition::Compare { subject, .. } => !matches!(subject, Subject::Hostname),
+            Condition::FileExists(_) | Condition::Env(_) => false,
+            Condition::Not(condition) => condition.is_live(),
+        }
+    }
+
+    /// Boolean whether the condition doesn't depend on the login form and isn't met
+    pub fn is_static_hidden(&self) -> bool {
+        !self.is_live() && !self.evaluate(&FormState::default())
+    }
+
+    pub fn evaluate(&self, state: &FormState) -> bool {
+        match self {
+            Condition::UserSelected => !state.username.is_empty(),
+            Condition::Compare { subject, operator, value } => {
+                let actual = match subject {
+                    Subject::Runner => state.runner.clone().unwrap_or_default(),
+                    Subject::Username => state.username.clone(),
+                    Subject::Hostname => hostname().unwrap_or_default(),
+                };
+                match operator {
+                    Operator::Equals => actual == *value,
+                    Operator::NotEquals => actual != *value,
+                    Operator::Matches => glob_matches(value, &actual),
+                }
+            },
+            Condition::FileExists(path) => Path::new(path).exists(),
+            Condition::Env(name) => std::env::var(name).is_ok_and(|value| !value.is_empty()),
+            Condition::Not(condition) => !condition.evaluate(state),
+        }
+    }
+
+    /// Parse an expression like `runner == "hyprland"`, `user_selected`,
+    /// `hostname matches "lab-*"`, `file_exists "/path"` or `env "VAR"`.
+    /// Each expression can be negated using a leading `!`
+    fn parse(expression: &str) -> Result<Self, String> {
+        let expression = expression.trim();
+        if let Some(negated) = expression.strip_prefix('!') {
+            return Ok(Condition::Not(Box::new(Self::parse(negated)?)));
+        }
+        if expression == "user_selected" {
+            return Ok(Condition::UserSelected);
+        }
+
+        let invalid = || format!("invalid condition {expression}");
+        let (name, rest) = expression.split_once(char::is_whitespace).ok_or_else(invalid)?;
+        let rest = rest.trim();
+        match name {
+            "file_exists" => return Ok(Condition::FileExists(unquote(rest))),
+            "env" => return Ok(Condition::Env(unquote(rest))),
+            _ => {}
+        }
+
+        let subject = match name {
+            "runner" => Subject::Runner,
+            "username" => Subject::Username,
+            "hostname" => Subject::Hostname,
+            _ => return Err(format!("unknown condition subject {name}"))
+        };
+        let (operator, value) = rest.split_once(char::is_whitespace).ok_or_else(invalid)?;
+        let operator = match operator {
+            "==" => Operator::Equals,
+            "!=" => Operator::NotEquals,
+            "matches" => Operator::Matches,
+            _ => return Err(format!("unknown condition operator {operator}"))
+        };
+        Ok(Condition::Compare { subject, operator, value: unquote(value) })
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Condition::UserSelected => write!(f, "user_selected"),
+            Condition::Compare { subject, operator, value } => {
+                let subject = match subject {
+                    Subject::Runner => "runner",
+                    Subject::Username => "username",
+                    Subject::Hostname => "hostname",
+                };
+                let operator = match operator {
+                    Operator::Equals => "==",
+                    Operator::NotEquals => "!=",
+                    Operator::Matches => "matches",
+                };
+                // values aren't escaped, as unquoting only strips the outer quotes
+                write!(f, "{subject} {operator} \"{value}\"")
+            },
+            Condition::FileExists(path) => write!(f, "file_exists \"{path}\""),
+            Condition::Env(name) => write!(f, "env \"{name}\""),
+            Condition::Not(condition) => write!(f, "!{condition}"),
+        }
+    }
+}
+
+impl JsonSchema for Condition {
+    fn schema_name() -> String {
+        String::from("Condition")
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        ConditionValue::json_schema(gen)
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(untagged)]
+enum ConditionValue {
+    /// Expression like `runner == "hyprland"`, `user_selected` or `hostname matches "lab-*"`
+    Expression(String),
+    FileExists {
+        /// Path which has to exist
+        file_exists: String
+    },
+    Env {
+        /// Environment variable which has to be set
+        env: String
+    }
+}
+
+impl TryFrom<ConditionValue> for Condition {
+    type Error = String;
+
+    fn try_from(value: ConditionValue) -> Result<Self, Self::Error> {
+        match value {
+            ConditionValue::Expression(expression) => Condition::parse(&expression),
+            ConditionValue::FileExists { file_exists } => Ok(Condition::FileExists(file_exists)),
+            ConditionValue::Env { env } => Ok(Condition::Env(env)),
+        }
+    }
+}
+
+impl From<Condition> for ConditionValue {
+    fn from(condition: Condition) -> Self {
+        match condition {
+            Condition::FileExists(file_exists) => ConditionValue::FileExists { file_exists },
+            Condition::Env(env) => ConditionValue::Env { env },
+            condition => ConditionValue::Expression(condition.to_string()),
+        }
+    }
+}
+
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    ['"', '\''].into_iter()
+        .find_map(|quote| value.strip_prefix(quote).and_then(|value| value.strip_suffix(quote)))
+        .unwrap_or(value)
+        .to_string()
+}
+
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let (pattern, text) = (pattern.chars().collect::<Vec<_>>(), text.chars().collect::<Vec<_>>());
+    let (mut p, mut t) = (0, 0);
+    // position of the last star in the pattern and the text position it was tried at
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Current state of the form fields
+fn form_state(fields: &FormFields, config: &Config) -> FormState {
+    let runner = match &fields.runner {
+        Some(runner) => {
+            let tmp = runner.as_ref().borrow();
+            let dropdown = tmp.downcast_ref::<DropDown>().expect("should be dropdown");
+            dropdown.selected_item().and_downcast::<StringObject>().and_then(|selected| {
+                config.runners.iter()
+                    .find(|(_, runner)| runner.display_name == selected.string())
+                    .map(|(name, _)| name.clone())
+            })
+        },
+        None => config.default_runner.clone()
+    };
+    let username = match &fields.username {
+        Some(username) => {
+            let tmp = username.as_ref().borrow();
+            tmp.downcast_ref::<Entry>().expect("should be entry").text().to_string()
+        },
+        None => config.username.clone().unwrap_or_default()
+    };
+    FormState { runner, username }
+}
+
+fn update_visibility(fields: &FormFields, config: &Config) {
+    let state = form_state(fields, config);
+    fields.conditions.iter().for_each(|(condition, conditional)| {
+        conditional.set_met(condition.evaluate(&state));
+    });
+}
+
+/// Evaluate the live conditions of the form and re-evaluate
+/// them whenever the runner or the username changes
+pub fn watch_conditions(fields: &FormFields, config: &Arc<Config>) {
+    if fields.conditions.is_empty() {
+        return;
+    }
+    update_visibility(fields, config);
+
+    if let Some(runner) = &fields.runner {
+        let (cf, cc) = (fields.clone(), config.clone());
+        let tmp = runner.as_ref().borrow();
+        let dropdown = tmp.downcast_ref::<DropDown>().expect("should be dropdown");
+        dropdown.connect_selected_notify(move |_| update_visibility(&cf, &cc));
+    }
+    if let Some(username) = &fields.username {
+        let (cf, cc) = (fields.clone(), config.clone());
+        let tmp = username.as_ref().borrow();
+        let entry = tmp.downcast_ref::<Entry>().expect("should be entry");
+        entry.connect_changed(move |_| update_visibility(&cf, &cc));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(runner: &str, username: &str) -> FormState {
+        FormState { runner: Some(String::from(runner)), username: String::from(username) }
+    }
+
+    #[test]
+    fn parse_negation() {
+        let condition = Condition::parse("!user_selected").unwrap();
+        assert!(matches!(&condition, Condition::Not(inner) if matches!(**inner, Condition::UserSelected)));
+        assert!(condition.evaluate(&state("sway", "")));
+        assert!(!condition.evaluate(&state("sway", "user")));
+
+        let condition = Condition::parse("! runner == sway").unwrap();
+        assert!(!condition.evaluate(&state("sway", "")));
+        assert!(condition.evaluate(&state("hyprland", "")));
+    }
+
+    #[test]
+    fn parse_quoted_and_unquoted_values() {
+        for expression in ["runner == sway", "runner == \"sway\"", "runner == 'sway'"] {
+            let condition = Condition::parse(expression).unwrap();
+            assert!(
+                matches!(&condition, Condition::Compare { subject: Subject::Runner, operator: Operator::Equals, value } if value == "sway"),
+                "{expression}"
+            );
+        }
+        let condition = Condition::parse("username != \"john doe\"").unwrap();
+        assert!(matches!(&condition, Condition::Compare { value, .. } if value == "john doe"));
+        assert!(matches!(Condition::parse("file_exists '/etc/hosts'").unwrap(), Condition::FileExists(path) if path == "/etc/hosts"));
+        assert!(matches!(Condition::parse("env WAYLAND_DISPLAY").unwrap(), Condition::Env(name) if name == "WAYLAND_DISPLAY"));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(Condition::parse("runner ~= sway").unwrap_err().contains("unknown condition operator"));
+        assert!(Condition::parse("shell == zsh").unwrap_err().contains("unknown condition subject"));
+        assert!(Condition::parse("runner").is_err());
+        assert!(Condition::parse("runner ==").is_err());
+    }
+
+    #[test]
+    fn glob_matching() {
+        assert!(glob_matches("lab-*", "lab-01"));
+        assert!(glob_matches("lab-*", "lab-"));
+        assert!(glob_matches("*", ""));
+        assert!(glob_matches("lab-??", "lab-01"));
+        assert!(!glob_matches("lab-??", "lab-1"));
+        assert!(!glob_matches("lab-*", "office-01"));
+        // the star has to give back characters to match the rest of the pattern
+        assert!(glob_matches("*ab", "aab"));
+        assert!(glob_matches("a*b*c", "axxbyybc"));
+        assert!(glob_matches("*-?1", "lab-x-01"));
+        assert!(!glob_matches("a*b", "abc"));
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        for (yaml, display) in [
+            ("runner == \"sway\"", "runner == \"sway\""),
+            ("hostname matches lab-*", "hostname matches \"lab-*\""),
+            ("\"!user_selected\"", "!user_selected"),
+            ("file_exists: /sys/class/power_supply/BAT0", "file_exists \"/sys/class/power_supply/BAT0\""),
+            ("env: WAYLAND_DISPLAY", "env \"WAYLAND_DISPLAY\""),
+        ] {
+            let condition: Condition = serde_yaml::from_str(yaml).unwrap();
+            assert_eq!(condition.to_string(), display);
+            let serialized = serde_yaml::to_string(&condition).unwrap();
+            let parsed: Condition = serde_yaml::from_str(&serialized).unwrap();
+            assert_eq!(parsed.to_string(), display);
+        }
+        assert!(serde_yaml::from_str::<Condition>("runner ~= sway").is_err());
+    }
+
+    #[test]
+    fn display_round_trip_with_quotes_and_backslashes() {
+        for value in ["say \"hi\"", "C:\\Users", "\"", "it's", ""] {
+            let condition = Condition::Compare { subject: Subject::Username, operator: Operator::Equals, value: value.to_string() };
+            let parsed = Condition::parse(&condition.to_string()).unwrap();
+            assert!(matches!(&parsed, Condition::Compare { value: actual, .. } if actual == value), "{condition}");
+        }
+        let parsed = Condition::parse(&Condition::FileExists(String::from("/tmp/a \"b\"")).to_string()).unwrap();
+        assert!(matches!(parsed, Condition::FileExists(path) if path == "/tmp/a \"b\""));
+    }
+}
@@ -1,9 +1,13 @@
 use gtk4 as gtk;
-use serde::Deserialize;
+use condition::Condition;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
 pub mod build;
+pub mod condition;
+pub mod status;
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub enum Component {
     /// Password form field component
@@ -12,32 +16,220 @@ pub enum Component {
     Username(UsernameComponent),
     /// Runner select form field component
     Runner(RunnerComponent),
+    /// Keyboard layout select component
+    #[serde(rename = "keyboard_layout")]
+    KeyboardLayout(KeyboardLayoutComponent),
     /// Box component
     Box(BoxComponent),
+    /// Grid component placing children in rows and columns
+    Grid(GridComponent),
+    /// Overlay component layering children on top of a main child
+    Overlay(OverlayComponent),
+    /// Center box component with a start, center and end slot
+    CenterBox(CenterBoxComponent),
+    /// Stack component showing one of multiple named pages
+    Stack(StackComponent),
     /// Label component displaying the current date time
     DateTime(DateTimeComponent),
     /// Label component displaying any text
     Label(LabelComponent),
+    /// Label component displaying messages of the login form
+    Message(MessageComponent),
     /// Button component to run a command on click
-    Button(ButtonComponent)
+    Button(ButtonComponent),
+    /// Indicator component displaying the caps lock and num lock state
+    LockKeys(LockKeysComponent),
+    /// Label component displaying the charge of a battery
+    Battery(BatteryComponent),
+    /// Label component displaying the network connectivity
+    Network(NetworkComponent),
+    /// Label component displaying information about the system
+    SysInfo(SysInfoComponent),
+    /// Read-only calendar component highlighting the current day
+    Calendar(CalendarComponent),
+    /// Analog clock component
+    Clock(ClockComponent)
 }
 
-#[derive(Deserialize, Clone, Debug)]
+impl Component {
+    /// Name of the component type as used in the layout tree
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Component::Password(_) => "password",
+            Component::Username(_) => "username",
+            Component::Runner(_) => "runner",
+            Component::KeyboardLayout(_) => "keyboard_layout",
+            Component::Box(_) => "box",
+            Component::Grid(_) => "grid",
+            Component::Overlay(_) => "overlay",
+            Component::CenterBox(_) => "centerbox",
+            Component::Stack(_) => "stack",
+            Component::DateTime(_) => "datetime",
+            Component::Label(_) => "label",
+            Component::Message(_) => "message",
+            Component::Button(_) => "button",
+            Component::LockKeys(_) => "lockkeys",
+            Component::Battery(_) => "battery",
+            Component::Network(_) => "network",
+            Component::SysInfo(_) => "sysinfo",
+            Component::Calendar(_) => "calendar",
+            Component::Clock(_) => "clock",
+        }
+    }
+
+    /// Boolean whether only the first component of this type in the layout tree is used
+    pub fn is_unique(&self) -> bool {
+        matches!(
+            self,
+            Component::Password(_) | Component::Username(_) | Component::Runner(_) |
+            Component::KeyboardLayout(_) | Component::Message(_)
+        )
+    }
+
+    /// Css classes which are applied to the component
+    pub fn classes(&self) -> &[String] {
+        match self {
+            Component::Password(c) => &c.classes,
+            Component::Username(c) => &c.classes,
+            Component::Runner(c) => &c.classes,
+            Component::KeyboardLayout(c) => &c.classes,
+            Component::Box(c) => &c.classes,
+            Component::Grid(c) => &c.classes,
+            Component::Overlay(c) => &c.classes,
+            Component::CenterBox(c) => &c.classes,
+            Component::Stack(c) => &c.classes,
+            Component::DateTime(c) => &c.classes,
+            Component::Label(c) => &c.classes,
+            Component::Message(c) => &c.classes,
+            Component::Button(c) => &c.classes,
+            Component::LockKeys(c) => &c.classes,
+            Component::Battery(c) => &c.classes,
+            Component::Network(c) => &c.classes,
+            Component::SysInfo(c) => &c.classes,
+            Component::Calendar(c) => &c.classes,
+            Component::Clock(c) => &c.classes,
+        }
+    }
+
+    /// Children of the component in the order they are built
+    pub fn children(&self) -> Vec<&Component> {
+        match self {
+            Component::Box(c) => c.children.iter().collect(),
+            Component::Grid(c) => c.children.iter().map(|child| &child.component).collect(),
+            Component::Overlay(c) => c.child.iter().map(|child| child.as_ref()).chain(c.overlays.iter()).collect(),
+            Component::CenterBox(c) => [&c.start, &c.center, &c.end].into_iter().flatten().map(|child| child.as_ref()).collect(),
+            Component::Stack(c) => c.pages.iter().map(|page| &page.component).collect(),
+            _ => Vec::new()
+        }
+    }
+
+    /// Common layout properties of the component
+    pub fn properties(&self) -> &WidgetProperties {
+        match self {
+            Component::Password(c) => &c.properties,
+            Component::Username(c) => &c.properties,
+            Component::Runner(c) => &c.properties,
+            Component::KeyboardLayout(c) => &c.properties,
+            Component::Box(c) => &c.properties,
+            Component::Grid(c) => &c.properties,
+            Component::Overlay(c) => &c.properties,
+            Component::CenterBox(c) => &c.properties,
+            Component::Stack(c) => &c.properties,
+            Component::DateTime(c) => &c.properties,
+            Component::Label(c) => &c.properties,
+            Component::Message(c) => &c.properties,
+            Component::Button(c) => &c.properties,
+            Component::LockKeys(c) => &c.properties,
+            Component::Battery(c) => &c.properties,
+            Component::Network(c) => &c.properties,
+            Component::SysInfo(c) => &c.properties,
+            Component::Calendar(c) => &c.properties,
+            Component::Clock(c) => &c.properties,
+        }
+    }
+
+    /// Static condition which isn't met, in which case
+    /// the component and its children are never built
+    pub fn hidden_by(&self) -> Option<&Condition> {
+        self.properties().visible_if.as_ref().filter(|condition| condition.is_static_hidden())
+    }
+}
+
+/// Layout properties which can be set on every component
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug, Default)]
+#[serde(default)]
+pub struct WidgetProperties {
+    /// Horizontal alignment of the component inside its parent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub halign: Option<Align>,
+    /// Vertical alignment of the component inside its parent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub valign: Option<Align>,
+    /// Boolean whether the component should expand horizontally
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hexpand: Option<bool>,
+    /// Boolean whether the component should expand vertically
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vexpand: Option<bool>,
+    /// Margin on all sides of the component
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub margin: Option<i32>,
+    /// Margin above the component, overrides `margin`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub margin_top: Option<i32>,
+    /// Margin below the component, overrides `margin`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub margin_bottom: Option<i32>,
+    /// Margin at the start of the component, overrides `margin`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub margin_start: Option<i32>,
+    /// Margin at the end of the component, overrides `margin`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub margin_end: Option<i32>,
+    /// Condition under which the component is visible
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visible_if: Option<Condition>,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
 #[serde(default)]
 pub struct RunnerComponent {
     /// Css classes which are applied to the runner dropdown
-    pub classes: Vec<String>
+    pub classes: Vec<String>,
+    /// Common layout properties of the component
+    #[serde(flatten)]
+    pub properties: WidgetProperties
 }
 
 impl Default for RunnerComponent {
     fn default() -> Self {
         Self {
-            classes: vec![String::from("runner"), String::from("dropdown")]
+            classes: vec![String::from("runner"), String::from("dropdown")],
+            properties: WidgetProperties::default()
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
+#[serde(default)]
+pub struct KeyboardLayoutComponent {
+    /// Css classes which are applied to the keyboard layout dropdown
+    pub classes: Vec<String>,
+    /// Common layout properties of the component
+    #[serde(flatten)]
+    pub properties: WidgetProperties
+}
+
+impl Default for KeyboardLayoutComponent {
+    fn default() -> Self {
+        Self {
+            classes: vec![String::from("keyboard-layout"), String::from("dropdown")],
+            properties: WidgetProperties::default()
         }
     }
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
 #[serde(default)]
 pub struct DateTimeComponent {
     /// Css classes which are applied to the datetime label
@@ -48,6 +240,9 @@ pub struct DateTimeComponent {
     pub format: String,
     /// Milliseconds after which the date time should be updated
     pub interval: u64,
+    /// Common layout properties of the component
+    #[serde(flatten)]
+    pub properties: WidgetProperties
 }
 
 impl Default for DateTimeComponent {
@@ -55,86 +250,128 @@ impl Default for DateTimeComponent {
         Self {
             classes: vec![String::from("label"), String::from("datetime")],
             format: String::from("%H:%M"),
-            interval: 1000
+            interval: 1000,
+            properties: WidgetProperties::default()
         }
     }
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
 pub struct LabelComponent {
     /// Css classes which are applied to the label
     #[serde(default)]
     pub classes: Vec<String>,
     /// Static text value of the label
-    pub label: String
+    pub label: String,
+    /// Common layout properties of the component
+    #[serde(flatten)]
+    pub properties: WidgetProperties
 }
 
 impl Default for LabelComponent {
     fn default() -> Self {
         Self {
             classes: vec![String::from("label")],
-            label: String::new()
+            label: String::new(),
+            properties: WidgetProperties::default()
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
+#[serde(default)]
+pub struct MessageComponent {
+    /// Css classes which are applied to the message label
+    pub classes: Vec<String>,
+    /// Common layout properties of the component
+    #[serde(flatten)]
+    pub properties: WidgetProperties
+}
+
+impl Default for MessageComponent {
+    fn default() -> Self {
+        Self {
+            classes: vec![String::from("label"), String::from("message")],
+            properties: WidgetProperties::default()
         }
     }
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
 #[serde(default)]
 pub struct UsernameComponent {
     /// Css classes which are applied to the form field
     pub classes: Vec<String>,
     /// Placeholder text for the form field
     pub placeholder: String,
+    /// Boolean whether the field should be reset to the default username after a failed login attempt
+    pub clear_on_error: bool,
+    /// Common layout properties of the component
+    #[serde(flatten)]
+    pub properties: WidgetProperties
 }
 
 impl Default for UsernameComponent {
     fn default() -> Self {
         Self {
             classes: vec![String::from("input"), String::from("username")],
-            placeholder: String::from("Username")
+            placeholder: String::from("Username"),
+            clear_on_error: false,
+            properties: WidgetProperties::default()
         }
     }
 }
 
-#[derive(Deserialize, Clone, Debug)]
+/// Password field which is always cleared after a login attempt, as the
+/// password is wiped from memory once it's sent and can't be restored
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
 #[serde(default)]
 pub struct PasswordComponent {
     /// Css classes which are applied to the form field
     pub classes: Vec<String>,
     /// Placeholder text for the form field
     pub placeholder: String,
+    /// Boolean whether an icon to reveal the password should be shown
+    pub show_peek_icon: bool,
+    /// Character which is displayed instead of the actual characters
+    pub invisible_char: Option<char>,
+    /// Common layout properties of the component
+    #[serde(flatten)]
+    pub properties: WidgetProperties
 }
 
 impl Default for PasswordComponent {
     fn default() -> Self {
         Self {
             classes: vec![String::from("input"), String::from("password")],
-            placeholder: String::from("Password")
+            placeholder: String::from("Password"),
+            show_peek_icon: false,
+            invisible_char: None,
+            properties: WidgetProperties::default()
         }
     }
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
 #[serde(default)]
 pub struct BoxComponent {
     /// Css classes which are applied to the box
     pub classes: Vec<String>,
-    /// Horizontal alignment of children in the box
-    pub halign: Align,
-    /// Vertical alignment of children in the box
-    pub valign: Align,
-    /// Boolean whether the box should expand horizontally
-    pub hexpand: bool,
-    /// Boolean whether the box should expand vertically
-    pub vexpand: bool,
     /// Requested width of the box
     pub width: i32,
+    /// Requested height of the box
+    pub height: i32,
+    /// Boolean whether all children should get the same size
+    pub homogeneous: bool,
     /// Spacing between the children of the box
     pub spacing: i32,
     /// Orientation of the children of the box
     pub orientation: Orientation,
     /// Children of the box component
-    pub children: Vec<Component>
+    pub children: Vec<Component>,
+    /// Common layout properties of the component
+    #[serde(flatten)]
+    pub properties: WidgetProperties
 }
 
 impl Default for BoxComponent {
@@ -144,16 +381,203 @@ impl Default for BoxComponent {
             orientation: Orientation::Vertical,
             children: Vec::new(),
             classes: vec![String::from("box")],
-            halign: Align::Center,
-            valign: Align::Center,
-            hexpand: true,
-            vexpand: true,
-            width: 500
+            width: 500,
+            height: -1,
+            homogeneous: false,
+            properties: WidgetProperties::default()
         }
     }
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
+#[serde(default)]
+pub struct GridComponent {
+    /// Css classes which are applied to the grid
+    pub classes: Vec<String>,
+    /// Spacing between the rows of the grid
+    pub row_spacing: i32,
+    /// Spacing between the columns of the grid
+    pub column_spacing: i32,
+    /// Boolean whether all rows should have the same height
+    pub row_homogeneous: bool,
+    /// Boolean whether all columns should have the same width
+    pub column_homogeneous: bool,
+    /// Children of the grid component with their position
+    pub children: Vec<GridChild>,
+    /// Common layout properties of the component
+    #[serde(flatten)]
+    pub properties: WidgetProperties
+}
+
+impl Default for GridComponent {
+    fn default() -> Self {
+        Self {
+            classes: vec![String::from("grid")],
+            row_spacing: 12,
+            column_spacing: 12,
+            row_homogeneous: false,
+            column_homogeneous: false,
+            children: Vec::new(),
+            properties: WidgetProperties::default()
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
+pub struct GridChild {
+    /// Row in which the child is placed
+    #[serde(default)]
+    pub row: i32,
+    /// Column in which the child is placed
+    #[serde(default)]
+    pub column: i32,
+    /// Number of rows the child spans
+    #[serde(default = "default_span")]
+    pub row_span: i32,
+    /// Number of columns the child spans
+    #[serde(default = "default_span")]
+    pub column_span: i32,
+    /// Component which is placed in the grid cell
+    #[serde(flatten)]
+    pub component: Component
+}
+
+fn default_span() -> i32 {
+    1
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
+#[serde(default)]
+pub struct OverlayComponent {
+    /// Css classes which are applied to the overlay
+    pub classes: Vec<String>,
+    /// Main child of the overlay which determines its size
+    pub child: Option<Box<Component>>,
+    /// Children which are layered on top of the main child
+    /// and positioned using their own alignment
+    pub overlays: Vec<Component>,
+    /// Common layout properties of the component
+    #[serde(flatten)]
+    pub properties: WidgetProperties
+}
+
+impl Default for OverlayComponent {
+    fn default() -> Self {
+        Self {
+            classes: vec![String::from("overlay")],
+            child: None,
+            overlays: Vec::new(),
+            properties: WidgetProperties::default()
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
+#[serde(default)]
+pub struct CenterBoxComponent {
+    /// Css classes which are applied to the center box
+    pub classes: Vec<String>,
+    /// Orientation of the slots of the center box
+    pub orientation: Orientation,
+    /// Component which is placed at the start
+    pub start: Option<Box<Component>>,
+    /// Component which is placed in the center
+    pub center: Option<Box<Component>>,
+    /// Component which is placed at the end
+    pub end: Option<Box<Component>>,
+    /// Common layout properties of the component
+    #[serde(flatten)]
+    pub properties: WidgetProperties
+}
+
+impl Default for CenterBoxComponent {
+    fn default() -> Self {
+        Self {
+            classes: vec![String::from("centerbox")],
+            orientation: Orientation::Horizontal,
+            start: None,
+            center: None,
+            end: None,
+            properties: WidgetProperties::default()
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
+#[serde(default)]
+pub struct StackComponent {
+    /// Css classes which are applied to the stack
+    pub classes: Vec<String>,
+    /// Animation used when switching between pages
+    pub transition: StackTransition,
+    /// Duration of the page transition in milliseconds
+    pub transition_duration: u32,
+    /// Name of the page which is visible initially
+    pub visible: Option<String>,
+    /// Boolean whether a switcher with the page titles should be shown above the stack
+    pub switcher: bool,
+    /// Named pages of the stack
+    pub pages: Vec<StackPage>,
+    /// Common layout properties of the component
+    #[serde(flatten)]
+    pub properties: WidgetProperties
+}
+
+impl Default for StackComponent {
+    fn default() -> Self {
+        Self {
+            classes: vec![String::from("stack")],
+            transition: StackTransition::Crossfade,
+            transition_duration: 200,
+            visible: None,
+            switcher: false,
+            pages: Vec::new(),
+            properties: WidgetProperties::default()
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
+pub struct StackPage {
+    /// Unique name of the page
+    pub name: String,
+    /// Title of the page displayed in the switcher
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Component which is displayed on the page
+    #[serde(flatten)]
+    pub component: Component
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum StackTransition {
+    None,
+    Crossfade,
+    SlideLeft,
+    SlideRight,
+    SlideUp,
+    SlideDown,
+    SlideLeftRight,
+    SlideUpDown
+}
+
+impl From<StackTransition> for gtk::StackTransitionType {
+    fn from(value: StackTransition) -> Self {
+        match value {
+            StackTransition::None => gtk::StackTransitionType::None,
+            StackTransition::Crossfade => gtk::StackTransitionType::Crossfade,
+            StackTransition::SlideLeft => gtk::StackTransitionType::SlideLeft,
+            StackTransition::SlideRight => gtk::StackTransitionType::SlideRight,
+            StackTransition::SlideUp => gtk::StackTransitionType::SlideUp,
+            StackTransition::SlideDown => gtk::StackTransitionType::SlideDown,
+            StackTransition::SlideLeftRight => gtk::StackTransitionType::SlideLeftRight,
+            StackTransition::SlideUpDown => gtk::StackTransitionType::SlideUpDown,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
 #[serde(rename_all = "lowercase")]
 pub enum Align {
     Fill,
@@ -175,7 +599,7 @@ impl From<Align> for gtk::Align {
     }
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
 #[serde(rename_all = "lowercase")]
 pub enum Orientation {
     Horizontal,
@@ -191,7 +615,7 @@ impl From<Orientation> for gtk::Orientation {
     }
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
 pub struct ButtonComponent {
     /// Label displayed in the button
     label: String,
@@ -199,7 +623,10 @@ pub struct ButtonComponent {
     /// Css classes which are applied to the button
     classes: Vec<String>,
     /// Command to run when the button is clicked
-    command: String
+    command: String,
+    /// Common layout properties of the component
+    #[serde(flatten)]
+    pub properties: WidgetProperties
 }
 
 impl Default for ButtonComponent {
@@ -207,7 +634,206 @@ impl Default for ButtonComponent {
         Self {
             command: String::new(),
             classes: vec![String::from("button")],
-            label: String::new()
+            label: String::new(),
+            properties: WidgetProperties::default()
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
+#[serde(default)]
+pub struct LockKeysComponent {
+    /// Css classes which are applied to the indicator box
+    pub classes: Vec<String>,
+    /// Text which is displayed while caps lock is active, hidden if not set
+    pub caps_lock: Option<String>,
+    /// Text which is displayed while num lock is active, hidden if not set
+    pub num_lock: Option<String>,
+    /// Spacing between the caps lock and num lock labels
+    pub spacing: i32,
+    /// Common layout properties of the component
+    #[serde(flatten)]
+    pub properties: WidgetProperties
+}
+
+impl Default for LockKeysComponent {
+    fn default() -> Self {
+        Self {
+            classes: vec![String::from("lockkeys")],
+            caps_lock: Some(String::from("Caps Lock is on")),
+            num_lock: None,
+            spacing: 12,
+            properties: WidgetProperties::default()
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
+#[serde(default)]
+pub struct BatteryComponent {
+    /// Css classes which are applied to the battery label
+    pub classes: Vec<String>,
+    /// Format of the label, `{capacity}`, `{status}` and `{name}` are replaced by the battery state
+    pub format: String,
+    /// Milliseconds after which the battery state should be updated
+    pub interval: u64,
+    /// Capacity in percent at or below which the low class is applied
+    pub low: u8,
+    /// Name of the power supply (e.g. BAT0), the first battery is used if not set
+    pub supply: Option<String>,
+    /// Directory containing the power supplies
+    pub path: String,
+    /// Common layout properties of the component
+    #[serde(flatten)]
+    pub properties: WidgetProperties
+}
+
+impl Default for BatteryComponent {
+    fn default() -> Self {
+        Self {
+            classes: vec![String::from("label"), String::from("battery")],
+            format: String::from("{capacity}%"),
+            interval: 10000,
+            low: 20,
+            supply: None,
+            path: String::from("/sys/class/power_supply"),
+            properties: WidgetProperties::default()
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
+#[serde(default)]
+pub struct NetworkComponent {
+    /// Css classes which are applied to the network label
+    pub classes: Vec<String>,
+    /// Format of the label while online, `{interface}`, `{address}` and `{state}` are replaced by the network state
+    pub format: String,
+    /// Format of the label while offline, supports the same placeholders
+    pub format_offline: String,
+    /// Milliseconds after which the network state should be updated
+    pub interval: u64,
+    /// Name of the network interface (e.g. eth0), the first connected interface is used if not set
+    pub interface: Option<String>,
+    /// Directory containing the network interfaces
+    pub path: String,
+    /// Common layout properties of the component
+    #[serde(flatten)]
+    pub properties: WidgetProperties
+}
+
+impl Default for NetworkComponent {
+    fn default() -> Self {
+        Self {
+            classes: vec![String::from("label"), String::from("network")],
+            format: String::from("{interface}: {address}"),
+            format_offline: String::from("Offline"),
+            interval: 5000,
+            interface: None,
+            path: String::from("/sys/class/net"),
+            properties: WidgetProperties::default()
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
+#[serde(default)]
+pub struct SysInfoComponent {
+    /// Css classes which are applied to the system information label
+    pub classes: Vec<String>,
+    /// Format of the label, `{hostname}`, `{os}`, `{kernel}`, `{cpu}`, `{uptime}`,
+    /// `{memory}`, `{memory_used}` and `{memory_total}` are replaced by the system information
+    pub format: String,
+    /// Milliseconds after which the changing fields like uptime and memory should be updated
+    pub interval: u64,
+    /// Common layout properties of the component
+    #[serde(flatten)]
+    pub properties: WidgetProperties
+}
+
+impl Default for SysInfoComponent {
+    fn default() -> Self {
+        Self {
+            classes: vec![String::from("label"), String::from("sysinfo")],
+            format: String::from("{hostname} · {os}"),
+            interval: 60000,
+            properties: WidgetProperties::default()
         }
     }
-}
\ No newline at end of file
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
+#[serde(default)]
+pub struct CalendarComponent {
+    /// Css classes which are applied to the calendar
+    pub classes: Vec<String>,
+    /// Boolean whether the month and year are shown above the days
+    pub show_heading: bool,
+    /// Boolean whether the names of the weekdays are shown
+    pub show_day_names: bool,
+    /// Boolean whether the week numbers are shown
+    pub show_week_numbers: bool,
+    /// Milliseconds after which the selected day should be updated
+    pub interval: u64,
+    /// Common layout properties of the component
+    #[serde(flatten)]
+    pub properties: WidgetProperties
+}
+
+impl Default for CalendarComponent {
+    fn default() -> Self {
+        Self {
+            classes: vec![String::from("calendar")],
+            show_heading: true,
+            show_day_names: true,
+            show_week_numbers: false,
+            interval: 60000,
+            properties: WidgetProperties::default()
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
+#[serde(default)]
+pub struct ClockComponent {
+    /// Css classes which are applied to the clock
+    pub classes: Vec<String>,
+    /// Width and height of the clock
+    pub size: i32,
+    /// Boolean whether the second hand is drawn
+    pub second_hand: bool,
+    /// Width of the hour and minute hands, the second hand is drawn with half the width
+    pub hand_width: f64,
+    /// Marks which are drawn around the clock face
+    pub ticks: ClockTicks,
+    /// Milliseconds after which the clock should be redrawn
+    pub interval: u64,
+    /// Common layout properties of the component
+    #[serde(flatten)]
+    pub properties: WidgetProperties
+}
+
+impl Default for ClockComponent {
+    fn default() -> Self {
+        Self {
+            classes: vec![String::from("clock")],
+            size: 200,
+            second_hand: true,
+            hand_width: 4.0,
+            ticks: ClockTicks::Hours,
+            interval: 1000,
+            properties: WidgetProperties::default()
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Copy, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum ClockTicks {
+    /// No marks
+    None,
+    /// A mark for each hour
+    Hours,
+    /// A mark for each minute with longer marks for the hours
+    Minutes
+}
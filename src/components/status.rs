@@ -0,0 +1,313 @@
+use std::{
+    fs,
+    net::{IpAddr, SocketAddrV4, SocketAddrV6},
+    path::Path
+};
+
+use log::warn;
+use nix::ifaddrs::getifaddrs;
+
+use crate::config::hostname;
+
+/// Interface which is never picked automatically for the network state
+const LOOPBACK_INTERFACE: &str = "lo";
+const OS_RELEASE_PATH: &str = "/etc/os-release";
+const KERNEL_RELEASE_PATH: &str = "/proc/sys/kernel/osrelease";
+const UPTIME_PATH: &str = "/proc/uptime";
+const MEMINFO_PATH: &str = "/proc/meminfo";
+const CPUINFO_PATH: &str = "/proc/cpuinfo";
+/// System information fields whose value changes while the greeter is running
+const CHANGING_SYSINFO_FIELDS: [&str; 3] = ["uptime", "memory", "memory_used"];
+
+/// Replace all `{name}` placeholders in the format with the value returned
+/// for the name, unknown placeholders are kept as they are
+pub fn format_placeholders(format: &str, mut value: impl FnMut(&str) -> Option<String>) -> String {
+    let mut result = String::with_capacity(format.len());
+    let mut rest = format;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}').map(|end| start + end) else {
+            break;
+        };
+        result.push_str(&rest[..start]);
+        match value(&rest[start + 1..end]) {
+            Some(value) => result.push_str(&value),
+            None => result.push_str(&rest[start..=end]),
+        }
+        rest = &rest[end + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Trimmed content of a file or `None` if it can't be read
+pub fn read_trimmed(path: impl AsRef<Path>) -> Option<String> {
+    fs::read_to_string(path).ok().map(|content| content.trim().to_string())
+}
+
+/// State of a battery power supply
+pub struct BatteryState {
+    /// Name of the power supply (e.g. BAT0)
+    pub name: String,
+    /// Charge in percent
+    pub capacity: u8,
+    /// Charging status as reported by the kernel (e.g. Charging)
+    pub status: String,
+}
+
+impl BatteryState {
+    /// Css class of the charging status (e.g. `not-charging`)
+    pub fn status_class(&self) -> String {
+        self.status.to_lowercase().replace(' ', "-")
+    }
+}
+
+/// Read the state of the named power supply or of the first battery inside the directory
+pub fn read_battery(root: &Path, supply: Option<&str>) -> Option<BatteryState> {
+    let directory = match supply {
+        Some(supply) => root.join(supply),
+        None => {
+            let mut supplies = fs::read_dir(root).ok()?
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| read_trimmed(path.join("type")).is_some_and(|kind| kind == "Battery"))
+                .collect::<Vec<_>>();
+            supplies.sort();
+            supplies.into_iter().next()?
+        }
+    };
+
+    Some(BatteryState {
+        name: directory.file_name()?.to_string_lossy().to_string(),
+        capacity: read_trimmed(directory.join("capacity"))?.parse().ok()?,
+        status: read_trimmed(directory.join("status")).unwrap_or_else(|| String::from("Unknown")),
+    })
+}
+
+/// State of a network interface
+pub struct NetworkState {
+    /// Name of the interface (e.g. eth0)
+    pub interface: String,
+    /// Operational state as reported by the kernel (e.g. up)
+    pub state: String,
+    /// First ipv4 or global ipv6 address of the interface
+    pub address: Option<IpAddr>,
+}
+
+impl NetworkState {
+    /// Boolean whether the interface is up and has an address
+    pub fn is_online(&self) -> bool {
+        self.state == "up" && self.address.is_some()
+    }
+}
+
+/// Read the state of the named interface or of the first interface which is up
+pub fn read_network(root: &Path, interface: Option<&str>) -> Option<NetworkState> {
+    let interface = match interface {
+        Some(interface) => interface.to_string(),
+        None => {
+            let mut interfaces = fs::read_dir(root).ok()?
+                .filter_map(|entry| entry.ok().map(|entry| entry.file_name().to_string_lossy().to_string()))
+                .filter(|name| name != LOOPBACK_INTERFACE)
+                .filter(|name| read_trimmed(root.join(name).join("operstate")).is_some_and(|state| state == "up"))
+                .collect::<Vec<_>>();
+            interfaces.sort();
+            interfaces.into_iter().next()?
+        }
+    };
+
+    Some(NetworkState {
+        state: read_trimmed(root.join(&interface).join("operstate")).unwrap_or_else(|| String::from("unknown")),
+        address: interface_address(&interface),
+        interface,
+    })
+}
+
+fn interface_address(interface: &str) -> Option<IpAddr> {
+    let addresses = match getifaddrs() {
+        Ok(addresses) => addresses,
+        Err(err) => {
+            warn!("unable to read network addresses: {err}");
+            return None;
+        }
+    };
+
+    let addresses = addresses
+        .filter(|address| address.interface_name == interface)
+        .filter_map(|address| address.address)
+        .collect::<Vec<_>>();
+    let ipv4 = addresses.iter()
+        .filter_map(|address| address.as_sockaddr_in())
+        .map(|address| IpAddr::V4(*SocketAddrV4::from(*address).ip()))
+        .next();
+    // link local addresses don't give any connectivity
+    let ipv6 = || addresses.iter()
+        .filter_map(|address| address.as_sockaddr_in6())
+        .map(|address| *SocketAddrV6::from(*address).ip())
+        .find(|address| (address.segments()[0] & 0xffc0) != 0xfe80)
+        .map(IpAddr::V6);
+    ipv4.or_else(ipv6)
+}
+
+/// Names of all placeholders used in the format
+pub fn placeholder_names(format: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    format_placeholders(format, |name| {
+        names.push(name.to_string());
+        None
+    });
+    names
+}
+
+/// Boolean whether the value of the system information field changes over time
+pub fn is_changing_sysinfo(name: &str) -> bool {
+    CHANGING_SYSINFO_FIELDS.contains(&name)
+}
+
+/// Current value of a system information field or `None` for unknown fields
+pub fn read_sysinfo(name: &str) -> Option<String> {
+    let value = match name {
+        "hostname" => hostname(),
+        "os" => read_os_name(),
+        "kernel" => read_trimmed(KERNEL_RELEASE_PATH),
+        "cpu" => read_cpu_model(),
+        "uptime" => read_uptime(),
+        "memory" => read_memory().map(|(used, total)| format!("{} / {}", format_kib(used), format_kib(total))),
+        "memory_used" => read_memory().map(|(used, _)| format_kib(used)),
+        "memory_total" => read_memory().map(|(_, total)| format_kib(total)),
+        _ => return None
+    };
+    Some(value.unwrap_or_default())
+}
+
+fn read_os_name() -> Option<String> {
+    fs::read_to_string(OS_RELEASE_PATH).ok()?
+        .lines()
+        .find_map(|line| line.strip_prefix("PRETTY_NAME="))
+        .map(|name| name.trim_matches('"').to_string())
+}
+
+fn read_cpu_model() -> Option<String> {
+    fs::read_to_string(CPUINFO_PATH).ok()?
+        .lines()
+        .find(|line| line.starts_with("model name"))
+        .and_then(|line| line.split_once(':'))
+        .map(|(_, model)| model.trim().to_string())
+}
+
+fn read_uptime() -> Option<String> {
+    let uptime = read_trimmed(UPTIME_PATH)?;
+    let seconds = uptime.split_whitespace().next()?.parse::<f64>().ok()? as u64;
+    let (days, hours, minutes) = (seconds / 86400, seconds % 86400 / 3600, seconds % 3600 / 60);
+    Some(match (days, hours) {
+        (0, 0) => format!("{minutes}m"),
+        (0, _) => format!("{hours}h {minutes}m"),
+        _ => format!("{days}d {hours}h {minutes}m"),
+    })
+}
+
+/// Used and total memory in KiB
+fn read_memory() -> Option<(u64, u64)> {
+    let meminfo = fs::read_to_string(MEMINFO_PATH).ok()?;
+    let value = |key: &str| meminfo.lines()
+        .find_map(|line| line.strip_prefix(key))
+        .and_then(|line| line.trim_start_matches(':').split_whitespace().next())
+        .and_then(|value| value.parse::<u64>().ok());
+    let (total, available) = (value("MemTotal")?, value("MemAvailable")?);
+    Some((total.saturating_sub(available), total))
+}
+
+fn format_kib(kib: u64) -> String {
+    format!("{:.1} GiB", kib as f64 / 1024.0 / 1024.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Create a fake sysfs device directory with the given attribute files
+    fn device(root: &Path, name: &str, files: &[(&str, &str)]) {
+        let directory = root.join(name);
+        fs::create_dir_all(&directory).unwrap();
+        files.iter().for_each(|(file, content)| fs::write(directory.join(file), format!("{content}\n")).unwrap());
+    }
+
+    #[test]
+    fn battery_picks_first_battery() {
+        let root = tempfile::tempdir().unwrap();
+        device(root.path(), "AC", &[("type", "Mains"), ("online", "1")]);
+        device(root.path(), "BAT1", &[("type", "Battery"), ("capacity", "40"), ("status", "Charging")]);
+        device(root.path(), "BAT0", &[("type", "Battery"), ("capacity", "87"), ("status", "Not charging")]);
+
+        let battery = read_battery(root.path(), None).unwrap();
+        assert_eq!(battery.name, "BAT0");
+        assert_eq!(battery.capacity, 87);
+        assert_eq!(battery.status, "Not charging");
+        assert_eq!(battery.status_class(), "not-charging");
+
+        let battery = read_battery(root.path(), Some("BAT1")).unwrap();
+        assert_eq!(battery.capacity, 40);
+        assert_eq!(battery.status_class(), "charging");
+    }
+
+    #[test]
+    fn battery_without_status_or_capacity() {
+        let root = tempfile::tempdir().unwrap();
+        device(root.path(), "BAT0", &[("type", "Battery"), ("capacity", "5")]);
+        device(root.path(), "BAT1", &[("type", "Battery"), ("capacity", "full")]);
+
+        let battery = read_battery(root.path(), None).unwrap();
+        assert_eq!(battery.status, "Unknown");
+        assert_eq!(battery.status_class(), "unknown");
+        assert!(read_battery(root.path(), Some("BAT1")).is_none());
+        assert!(read_battery(root.path(), Some("BAT2")).is_none());
+    }
+
+    #[test]
+    fn battery_missing() {
+        let root = tempfile::tempdir().unwrap();
+        device(root.path(), "AC", &[("type", "Mains")]);
+        assert!(read_battery(root.path(), None).is_none());
+        assert!(read_battery(&root.path().join("missing"), None).is_none());
+    }
+
+    #[test]
+    fn network_picks_first_interface_which_is_up() {
+        let root = tempfile::tempdir().unwrap();
+        device(root.path(), "lo", &[("operstate", "unknown")]);
+        device(root.path(), "wlan0", &[("operstate", "up")]);
+        device(root.path(), "eth0", &[("operstate", "down")]);
+        device(root.path(), "eth1", &[("operstate", "up")]);
+
+        let network = read_network(root.path(), None).unwrap();
+        assert_eq!(network.interface, "eth1");
+        assert_eq!(network.state, "up");
+
+        let network = read_network(root.path(), Some("eth0")).unwrap();
+        assert_eq!(network.state, "down");
+        assert!(!network.is_online());
+
+        let network = read_network(root.path(), Some("missing0")).unwrap();
+        assert_eq!(network.state, "unknown");
+    }
+
+    #[test]
+    fn network_without_interface_which_is_up() {
+        let root = tempfile::tempdir().unwrap();
+        device(root.path(), "lo", &[("operstate", "up")]);
+        device(root.path(), "eth0", &[("operstate", "down")]);
+        assert!(read_network(root.path(), None).is_none());
+    }
+
+    #[test]
+    fn placeholders() {
+        let value = |name: &str| match name {
+            "capacity" => Some(String::from("87")),
+            "status" => Some(String::from("Charging")),
+            _ => None
+        };
+        assert_eq!(format_placeholders("{capacity}% ({status})", value), "87% (Charging)");
+        assert_eq!(format_placeholders("no placeholders", value), "no placeholders");
+        assert_eq!(format_placeholders("{unknown} {capacity}", value), "{unknown} 87");
+        assert_eq!(format_placeholders("{capacity} {status", value), "87 {status");
+        assert_eq!(format_placeholders("{}", value), "{}");
+    }
+}
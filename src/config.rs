@@ -1,7 +1,11 @@
-use std::{collections::BTreeMap, fs, path::{Path, PathBuf}};
-use serde::Deserialize;
-use log::{error, warn};
+use std::{collections::BTreeMap, fs, path::{Path, PathBuf}, time::Duration};
+use schemars::{gen::SchemaGenerator, schema::Schema, JsonSchema};
+use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
+use log::{error, info, warn};
 
+use crate::actions::Action;
+use crate::templates;
 use crate::components::{
     BoxComponent,
     Component,
@@ -11,9 +15,20 @@ use crate::components::{
     UsernameComponent
 };
 
-#[derive(Deserialize, Debug)]
+/// Key of the config files which should be merged below a config file
+const INCLUDE_KEY: &str = "include";
+/// Directory next to the config file whose files are merged on top of the config
+const DROP_IN_DIRECTORY: &str = "config.d";
+/// Extensions of the config files which are applied from the drop-in directory
+const CONFIG_EXTENSIONS: [&str; 4] = ["yaml", "yml", "toml", "json"];
+/// Variable which is expanded to the hostname of the system
+const HOSTNAME_VARIABLE: &str = "hostname";
+const HOSTNAME_PATH: &str = "/proc/sys/kernel/hostname";
+
+#[derive(Deserialize, JsonSchema, Debug)]
 pub struct Config {
     #[serde(skip_deserializing)]
+    #[schemars(skip)]
     path: PathBuf,
     /// Named configuration for each monitor
     pub monitors: BTreeMap<String, Monitor>,
@@ -33,31 +48,139 @@ pub struct Config {
     /// Optional name of a runner which is preselected
     #[serde(default)]
     pub default_runner: Option<String>,
+    /// Named keyboard layout options
+    #[serde(default)]
+    pub keyboard_layouts: BTreeMap<String, KeyboardLayout>,
+    /// Optional name of a keyboard layout which is preselected
+    #[serde(default)]
+    pub default_keyboard_layout: Option<String>,
     /// Layout of the main monitor
     #[serde(default = "default_layout")]
     pub layout: Component,
+    /// Actions which are run when a key combination is pressed
+    #[serde(default = "default_keybindings")]
+    pub keybindings: BTreeMap<String, Action>,
+    /// Policy which throttles repeated failed login attempts
+    #[serde(default)]
+    pub attempts: AttemptPolicy,
+    /// Milliseconds after which the error class is removed from the form fields
+    #[serde(default)]
+    pub error_timeout: u64,
+    /// Optional idle mode which is entered after some time without input
+    #[serde(default)]
+    pub idle: Option<Idle>,
+    /// Milliseconds the opening class stays applied to the windows after they are shown
+    #[serde(default)]
+    pub opening_duration: u64,
+    /// Milliseconds to wait after a successful login before the greeter exits
+    #[serde(default)]
+    pub exit_delay: u64,
+    /// Optional command which is run on startup to turn on num lock
+    #[serde(default)]
+    pub numlock_cmd: Option<String>,
 }
 
 impl Config {
     pub fn new(path_str: &String) -> Self {
+        match Self::load(path_str) {
+            Ok(config) => config,
+            Err(err) => {
+                error!("{err}");
+                std::process::exit(1)
+            }
+        }
+    }
+
+    /// Load the config file together with its includes and drop-ins
+    pub fn load(path_str: &String) -> Result<Self, String> {
         let path = Path::new(path_str);
-        if path.exists() {
-            let str = fs::read_to_string(path).unwrap_or_default();
-            match serde_yaml::from_str(str.as_str()) {
-                Ok(config) => {
-                    Self { path: path.to_path_buf(), ..config }
-                },
-                Err(err) => {
-                    error!("invalid config file: {err}");
-                    std::process::exit(1)
-                }
+        if !path.exists() {
+            return Err(format!("missing config file at {}", path.to_str().unwrap_or_default()));
+        }
+
+        let mut sources = Vec::new();
+        let mut value = Self::load_value(path, &mut Vec::new(), &mut sources)?;
+        for drop_in in Self::drop_ins(path) {
+            merge_values(&mut value, Self::load_value(&drop_in, &mut Vec::new(), &mut sources)?);
+        }
+        templates::expand_templates(&mut value)?;
+        substitute_values(&mut value);
+
+        match serde_path_to_error::deserialize(value) {
+            Ok(config) => Ok(Self { path: path.to_path_buf(), ..config }),
+            Err(err) => {
+                let source = source_of(&sources, err.path()).unwrap_or(path);
+                Err(format!(
+                    "invalid config file {}: {} at {}",
+                    source.to_string_lossy(),
+                    err.inner(),
+                    err.path()
+                ))
             }
-        } else {
-            error!("missing config file at {}", path.to_str().unwrap_or_default());
-            std::process::exit(1)
         }
     }
 
+    /// Read a config file and merge it on top of all files it includes.
+    /// The files are merged in the order they are listed in `include` and
+    /// each file is added to the sources in the order it is merged
+    fn load_value(path: &Path, stack: &mut Vec<PathBuf>, sources: &mut Vec<(PathBuf, Value)>) -> Result<Value, String> {
+        let canonical = path.canonicalize().unwrap_or(path.to_path_buf());
+        if stack.contains(&canonical) {
+            return Err(format!("config file {} includes itself", path.to_string_lossy()));
+        }
+
+        let str = fs::read_to_string(path)
+            .map_err(|err| format!("unable to read config file {}: {err}", path.to_string_lossy()))?;
+        let mut value = match parse_value(path, &str) {
+            Ok(Value::Null) => Value::Mapping(Mapping::new()),
+            Ok(value) => value,
+            Err(err) => return Err(format!("invalid config file {}: {err}", path.to_string_lossy()))
+        };
+
+        let includes = match value.as_mapping_mut().and_then(|mapping| mapping.remove(INCLUDE_KEY)) {
+            None => Vec::new(),
+            Some(Value::String(include)) => vec![include],
+            Some(includes) => serde_yaml::from_value::<Vec<String>>(includes)
+                .map_err(|err| format!("invalid include in config file {}: {err}", path.to_string_lossy()))?
+        };
+
+        // includes are resolved relative to the file which includes them
+        let directory = path.parent().unwrap_or(path);
+        let mut merged = Value::Mapping(Mapping::new());
+        stack.push(canonical);
+        for include in includes {
+            let include_path = resolve_path(directory, &include);
+            info!("including config file {}", include_path.to_string_lossy());
+            merge_values(&mut merged, Self::load_value(&include_path, stack, sources)?);
+        }
+        stack.pop();
+
+        resolve_value_paths(&mut value, directory);
+        sources.push((path.to_path_buf(), value.clone()));
+        merge_values(&mut merged, value);
+        Ok(merged)
+    }
+
+    /// All config files in the drop-in directory next to the config file sorted by their name
+    fn drop_ins(path: &Path) -> Vec<PathBuf> {
+        let directory = path.parent().unwrap_or(path).join(DROP_IN_DIRECTORY);
+        let Ok(entries) = fs::read_dir(&directory) else {
+            return Vec::new();
+        };
+
+        let mut drop_ins = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.is_file() && path.extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| CONFIG_EXTENSIONS.contains(&ext))
+            })
+            .collect::<Vec<_>>();
+        drop_ins.sort();
+        drop_ins.iter().for_each(|drop_in| info!("applying config drop-in {}", drop_in.to_string_lossy()));
+        drop_ins
+    }
+
     pub fn path(&self) -> &PathBuf {
         &self.path
     }
@@ -90,23 +213,88 @@ impl Config {
     /// Resolve relative paths to position of config file
     /// and expand `$HOME` and `~` to user home directory
     pub fn resolve_path(&self, path_str: &String) -> PathBuf {
-        let path = match Self::expand_path(path_str) {
-            Some(path) => path,
-            None => {
-                warn!("unable to resolve user home directory");
-                Path::new(path_str).to_path_buf()
-            },
-        };
+        resolve_path(self.directory(), path_str)
+    }
+}
 
-        if path.is_relative() {
-            let full = self.directory().join(path);
-            full.canonicalize().unwrap_or(full)
-        } else {
-            path
+/// Resolve relative paths to the base directory
+/// and expand `$HOME` and `~` to user home directory
+fn resolve_path(base: &Path, path_str: &String) -> PathBuf {
+    let path = match Config::expand_path(path_str) {
+        Some(path) => path,
+        None => {
+            warn!("unable to resolve user home directory");
+            Path::new(path_str).to_path_buf()
+        },
+    };
+
+    if path.is_relative() {
+        let full = base.join(path);
+        full.canonicalize().unwrap_or(full)
+    } else {
+        path
+    }
+}
+
+/// Resolve the relative paths of stylesheets and backgrounds set by a config file
+/// against its own directory, so included files and drop-ins can refer to files
+/// next to them. Paths using variables are kept, as those are expanded later on
+fn resolve_value_paths(value: &mut Value, directory: &Path) {
+    fn resolve(value: &mut Value, directory: &Path) {
+        if let Value::String(path) = value {
+            // strings starting with `#` are hex colors instead of paths
+            if !path.contains("${") && !path.starts_with('#') {
+                *path = resolve_path(directory, path).to_string_lossy().to_string();
+            }
+        }
+    }
+
+    if let Some(styles) = value.get_mut("styles").and_then(Value::as_sequence_mut) {
+        styles.iter_mut().for_each(|style| resolve(style, directory));
+    }
+    if let Some(monitors) = value.get_mut("monitors").and_then(Value::as_mapping_mut) {
+        for background in monitors.values_mut().filter_map(|monitor| monitor.get_mut("background")) {
+            match background {
+                Value::Mapping(background) => ["directory", "image"].into_iter()
+                    .filter_map(|key| background.get_mut(key))
+                    .for_each(|path| resolve(path, directory)),
+                background => resolve(background, directory),
+            }
         }
     }
 }
 
+/// Config file which set the value at the path, which is the last merged file
+/// reaching furthest along the path. Components created from templates or the
+/// content of tagged enums may only be traced up to their parent
+fn source_of<'a>(sources: &'a [(PathBuf, Value)], path: &serde_path_to_error::Path) -> Option<&'a Path> {
+    sources.iter()
+        .map(|(source, value)| (source, depth_of(value, path)))
+        .filter(|(_, depth)| *depth > 0)
+        .max_by_key(|(_, depth)| *depth)
+        .map(|(source, _)| source.as_path())
+}
+
+/// Number of segments of the path which exist in the value
+fn depth_of(value: &Value, path: &serde_path_to_error::Path) -> usize {
+    let mut current = value;
+    let mut depth = 0;
+    for segment in path.iter() {
+        let next = match segment {
+            serde_path_to_error::Segment::Seq { index } => current.get(*index),
+            serde_path_to_error::Segment::Map { key } => current.get(key.as_str()),
+            serde_path_to_error::Segment::Enum { .. } => Some(current),
+            serde_path_to_error::Segment::Unknown => None,
+        };
+        match next {
+            Some(next) => current = next,
+            None => break
+        }
+        depth += 1;
+    }
+    depth
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
@@ -117,12 +305,108 @@ impl Default for Config {
             styles: Vec::new(),
             runners: BTreeMap::new(),
             default_runner: None,
+            keyboard_layouts: BTreeMap::new(),
+            default_keyboard_layout: None,
             classes: Classes::default(),
-            layout: default_layout()
+            layout: default_layout(),
+            keybindings: default_keybindings(),
+            attempts: AttemptPolicy::default(),
+            error_timeout: 0,
+            idle: None,
+            opening_duration: 0,
+            exit_delay: 0,
+            numlock_cmd: None
         }
     }
 }
 
+/// Parse the content of a config file using the format matching its extension,
+/// files with an unknown extension are parsed as yaml
+fn parse_value(path: &Path, str: &str) -> Result<Value, String> {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("toml") => toml::from_str::<toml::Value>(str)
+            .map_err(|err| err.to_string())
+            .and_then(|value| serde_yaml::to_value(value).map_err(|err| err.to_string())),
+        Some("json") => serde_json::from_str::<serde_json::Value>(str)
+            .map_err(|err| err.to_string())
+            .and_then(|value| serde_yaml::to_value(value).map_err(|err| err.to_string())),
+        _ => serde_yaml::from_str(str).map_err(|err| err.to_string())
+    }
+}
+
+/// Deep merge the overlay into the base value. Mappings are merged key by key
+/// while all other values, including sequences, of the overlay replace the base
+pub fn merge_values(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Mapping(base), Value::Mapping(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_values(existing, value),
+                    None => { base.insert(key, value); }
+                }
+            }
+        },
+        (base, overlay) => *base = overlay
+    }
+}
+
+/// Expand the variables in all strings of the value, keys of mappings are kept as they are
+fn substitute_values(value: &mut Value) {
+    match value {
+        Value::String(str) => *str = substitute(str),
+        Value::Sequence(sequence) => sequence.iter_mut().for_each(substitute_values),
+        Value::Mapping(mapping) => mapping.values_mut().for_each(substitute_values),
+        Value::Tagged(tagged) => substitute_values(&mut tagged.value),
+        _ => {}
+    }
+}
+
+/// Expand `${VAR}` and `${VAR:-default}` to the value of the environment variable
+/// and `${hostname}` to the hostname of the system. `$${` is kept as literal `${`
+fn substitute(str: &str) -> String {
+    let mut result = String::with_capacity(str.len());
+    let mut rest = str;
+    while let Some(start) = rest.find("${") {
+        if rest[..start].ends_with('$') {
+            result.push_str(&rest[..start - 1]);
+            result.push_str("${");
+            rest = &rest[start + 2..];
+            continue;
+        }
+
+        let Some(end) = rest[start..].find('}').map(|end| start + end) else {
+            break;
+        };
+        result.push_str(&rest[..start]);
+
+        let expression = &rest[start + 2..end];
+        let (name, default) = match expression.split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (expression, None)
+        };
+        let variable = if name == HOSTNAME_VARIABLE {
+            hostname()
+        } else {
+            std::env::var(name).ok()
+        };
+        match (variable.filter(|variable| !variable.is_empty()), default) {
+            (Some(variable), _) => result.push_str(&variable),
+            (None, Some(default)) => result.push_str(default),
+            (None, None) => warn!("variable {name} used in config is not set"),
+        }
+        rest = &rest[end + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Hostname of the system
+pub fn hostname() -> Option<String> {
+    fs::read_to_string(HOSTNAME_PATH)
+        .map(|hostname| hostname.trim().to_string())
+        .ok()
+}
+
 fn default_layout() -> Component {
     Component::Box(BoxComponent {
         children: vec![
@@ -135,24 +419,206 @@ fn default_layout() -> Component {
     })
 }
 
-#[derive(Deserialize, Debug)]
+fn default_keybindings() -> BTreeMap<String, Action> {
+    BTreeMap::from([
+        (String::from("Escape"), Action::ClearForm),
+        (String::from("Ctrl+U"), Action::ClearForm),
+    ])
+}
+
+#[derive(Deserialize, JsonSchema, Debug)]
 #[serde(untagged)]
 pub enum MonitorBackground {
-    /// Rgb value of a color
-    Rgb(u8, u8, u8),
+    /// Solid background color
+    Color(Color),
     /// Path to the background image
-    Image(String)
+    Image(String),
+    /// Directory from which the background images are picked
+    Directory(DirectoryBackground),
+    /// Combination of a color, image and gradient
+    Styled(StyledBackground)
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, JsonSchema, Debug)]
+pub struct DirectoryBackground {
+    /// Path to the directory containing the background images
+    pub directory: String,
+    /// Milliseconds after which the next image is shown, if not set
+    /// a single image is picked on start
+    #[serde(default)]
+    pub interval: Option<u64>,
+    /// Duration of the crossfade between two images in milliseconds
+    #[serde(default = "default_transition")]
+    pub transition: u64,
+    /// Boolean whether all monitors using the same directory should show the same image
+    #[serde(default)]
+    pub shared: bool,
+    /// Seed for the order of the images, a random order is used on each start if not set
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Options how the images are displayed
+    #[serde(flatten)]
+    pub options: BackgroundOptions,
+}
+
+#[derive(Deserialize, JsonSchema, Debug)]
+pub struct StyledBackground {
+    /// Color which is displayed below the image and gradient
+    #[serde(default)]
+    pub color: Option<Color>,
+    /// Path to the background image
+    #[serde(default)]
+    pub image: Option<String>,
+    /// Gradient which is displayed on top of the image
+    #[serde(default)]
+    pub gradient: Option<Gradient>,
+    /// Options how the background is displayed
+    #[serde(flatten)]
+    pub options: BackgroundOptions,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Default)]
+#[serde(default)]
+pub struct BackgroundOptions {
+    /// How the background image is fitted into the monitor
+    pub fit: BackgroundFit,
+    /// Radius in pixels with which the background is blurred
+    pub blur: Option<u32>,
+    /// Additional css declarations which are applied to the background window
+    pub css: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Default, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum BackgroundFit {
+    /// Scale the image to cover the whole monitor
+    #[default]
+    Cover,
+    /// Scale the image to be fully visible on the monitor
+    Contain,
+    /// Repeat the image in its original size
+    Tile,
+    /// Display the image in its original size in the center
+    Center
+}
+
+#[derive(Deserialize, JsonSchema, Debug)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Gradient {
+    /// Gradient along a straight line
+    Linear {
+        /// Direction of the gradient in degrees, `0` points upwards
+        #[serde(default = "default_gradient_angle")]
+        angle: f32,
+        /// Colors of the gradient
+        stops: Vec<ColorStop>
+    },
+    /// Gradient starting from the center
+    Radial {
+        /// Shape of the gradient, either `circle` or `ellipse`
+        #[serde(default)]
+        shape: RadialShape,
+        /// Colors of the gradient
+        stops: Vec<ColorStop>
+    }
+}
+
+fn default_gradient_angle() -> f32 {
+    180.0
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Default, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum RadialShape {
+    Circle,
+    #[default]
+    Ellipse
+}
+
+#[derive(Deserialize, JsonSchema, Debug)]
+#[serde(untagged)]
+pub enum ColorStop {
+    /// Color which is evenly distributed with the other stops
+    Color(Color),
+    /// Color at a position in percent
+    Positioned {
+        color: Color,
+        position: f32
+    }
+}
+
+/// Color which is either written as `[r, g, b]`, `[r, g, b, a]` with an alpha
+/// between `0` and `1` or as hex string (`#rgb`, `#rrggbb` or `#rrggbbaa`)
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(try_from = "ColorValue")]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: f32,
+}
+
+impl JsonSchema for Color {
+    fn schema_name() -> String {
+        String::from("Color")
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        ColorValue::json_schema(gen)
+    }
+}
+
+impl Color {
+    /// Css representation of the color
+    pub fn css(&self) -> String {
+        format!("rgba({},{},{},{})", self.r, self.g, self.b, self.a)
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(untagged)]
+enum ColorValue {
+    Rgb(u8, u8, u8),
+    Rgba(u8, u8, u8, f32),
+    Hex(String)
+}
+
+impl TryFrom<ColorValue> for Color {
+    type Error = String;
+
+    fn try_from(value: ColorValue) -> Result<Self, Self::Error> {
+        match value {
+            ColorValue::Rgb(r, g, b) => Ok(Self { r, g, b, a: 1.0 }),
+            ColorValue::Rgba(r, g, b, a) => Ok(Self { r, g, b, a: a.clamp(0.0, 1.0) }),
+            ColorValue::Hex(hex) => {
+                let invalid = || format!("invalid hex color {hex}");
+                let digits = hex.strip_prefix('#').filter(|d| d.chars().all(|c| c.is_ascii_hexdigit())).ok_or_else(invalid)?;
+                let expanded = match digits.len() {
+                    3 | 4 => digits.chars().flat_map(|c| [c, c]).collect::<String>(),
+                    6 | 8 => digits.to_string(),
+                    _ => return Err(invalid())
+                };
+                let channel = |i: usize| u8::from_str_radix(&expanded[i * 2..i * 2 + 2], 16).map_err(|_| invalid());
+                let a = if expanded.len() == 8 { channel(3)? as f32 / 255.0 } else { 1.0 };
+                Ok(Self { r: channel(0)?, g: channel(1)?, b: channel(2)?, a })
+            }
+        }
+    }
+}
+
+fn default_transition() -> u64 {
+    1000
+}
+
+#[derive(Deserialize, JsonSchema, Debug)]
 pub struct Monitor {
     /// Hardware output of the monitor (e.g. DP-1)
     pub output: String,
-    /// Path to the background image of the monitor
+    /// Background image, directory or color of the monitor
     pub background: Option<MonitorBackground>
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug)]
 pub struct Runner {
     /// Name which should be displayed when the runner is selected
     pub display_name: String,
@@ -167,7 +633,33 @@ pub struct Runner {
     pub exit_cmd: Option<String>
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug)]
+pub struct KeyboardLayout {
+    /// Name which should be displayed when the layout is selected
+    pub display_name: String,
+    /// Xkb layout name (e.g. de)
+    pub layout: String,
+    /// Optional xkb layout variant (e.g. nodeadkeys)
+    #[serde(default)]
+    pub variant: Option<String>,
+    /// Command to run when the layout is selected to switch
+    /// the layout of the compositor running the greeter
+    #[serde(default)]
+    pub switch_cmd: Option<String>
+}
+
+impl KeyboardLayout {
+    /// Environment variables which pass the layout to the started session
+    pub fn env(&self) -> Vec<String> {
+        let mut env = vec![format!("XKB_DEFAULT_LAYOUT={}", self.layout)];
+        if let Some(variant) = &self.variant {
+            env.push(format!("XKB_DEFAULT_VARIANT={variant}"));
+        }
+        env
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Debug)]
 #[serde(default)]
 pub struct Classes {
     /// Css classes which are applied to the background windows
@@ -178,6 +670,16 @@ pub struct Classes {
     pub field_error: String,
     /// Css class which is applied to the username/password fields when they are empty
     pub field_empty: String,
+    /// Css class which is applied to the password field while caps lock is active
+    pub caps_lock: String,
+    /// Css class which is applied to the main window while login attempts are throttled
+    pub throttled: String,
+    /// Css class which is applied to all windows while the greeter is idle
+    pub idle: String,
+    /// Css class which is applied to all windows while they are opening
+    pub opening: String,
+    /// Css class which is applied to all windows after a successful login until the greeter exits
+    pub closing: String,
 }
 
 impl Default for Classes {
@@ -187,6 +689,174 @@ impl Default for Classes {
             window: vec![String::from("window")],
             field_error: String::from("error"),
             field_empty: String::from("empty"),
+            caps_lock: String::from("caps-lock"),
+            throttled: String::from("throttled"),
+            idle: String::from("idle"),
+            opening: String::from("opening"),
+            closing: String::from("closing"),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Debug)]
+pub struct Idle {
+    /// Seconds without any input after which the greeter becomes idle
+    #[serde(default = "default_idle_timeout")]
+    pub timeout: u64,
+    /// Optional layout which replaces the form while the greeter is idle
+    #[serde(default)]
+    pub layout: Option<Component>,
+    /// Command to run when the greeter becomes idle
+    #[serde(default)]
+    pub cmd: Option<String>,
+    /// Command to run when the greeter wakes up from idle
+    #[serde(default)]
+    pub wake_cmd: Option<String>,
+}
+
+fn default_idle_timeout() -> u64 {
+    300
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Debug)]
+#[serde(default)]
+pub struct AttemptPolicy {
+    /// Delay in milliseconds after the first failed attempt of a username
+    /// which doubles with every further failed attempt
+    pub delay: u64,
+    /// Maximum delay in milliseconds between two attempts
+    pub max_delay: u64,
+    /// Number of failed attempts after which the username is locked for the cooldown
+    pub max_attempts: u32,
+    /// Seconds a username is locked after reaching the maximum number of failed attempts
+    pub cooldown: u64,
+}
+
+impl AttemptPolicy {
+    /// Delay before the next attempt after the given number of failed attempts
+    pub fn delay_after(&self, failures: u32) -> Duration {
+        if self.delay == 0 || failures == 0 {
+            return Duration::ZERO;
         }
+        let factor = 2u64.saturating_pow(failures - 1);
+        Duration::from_millis(self.delay.saturating_mul(factor).min(self.max_delay))
     }
-}
\ No newline at end of file
+}
+
+impl Default for AttemptPolicy {
+    fn default() -> Self {
+        Self {
+            delay: 0,
+            max_delay: 30000,
+            max_attempts: 0,
+            cooldown: 60,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schema_error_names_field_and_file() {
+        let directory = tempfile::tempdir().unwrap();
+        let base = directory.path().join("base.yaml");
+        fs::write(&base, "attempts:\n  delay: soon\n").unwrap();
+        let config = directory.path().join("config.yaml");
+        fs::write(&config, "include: base.yaml\nmonitors: {}\nmain_monitor: dp-1\nrunners: {}\n").unwrap();
+
+        let err = Config::load(&config.to_string_lossy().to_string()).unwrap_err();
+        assert!(err.contains("attempts.delay"), "{err}");
+        assert!(err.contains(&base.to_string_lossy().to_string()), "{err}");
+    }
+
+    #[test]
+    fn included_paths_are_resolved_from_their_file() {
+        let directory = tempfile::tempdir().unwrap();
+        let theme = directory.path().join("theme");
+        fs::create_dir(&theme).unwrap();
+        fs::write(theme.join("theme.yaml"), "styles: [ style.css ]\nmonitors:\n  dp-1:\n    output: DP-1\n    background: { image: bg.png }\n  dp-2:\n    output: DP-2\n    background: \"#2e3440\"\n").unwrap();
+        let config = directory.path().join("config.yaml");
+        fs::write(&config, "include: theme/theme.yaml\nmain_monitor: dp-1\nrunners: {}\n").unwrap();
+
+        let config = Config::load(&config.to_string_lossy().to_string()).unwrap();
+        assert_eq!(config.resolve_path(&config.styles[0]), theme.join("style.css"));
+        let Some(MonitorBackground::Styled(background)) = &config.monitors["dp-1"].background else {
+            panic!("background should be styled");
+        };
+        assert_eq!(config.resolve_path(background.image.as_ref().unwrap()), theme.join("bg.png"));
+        assert!(matches!(config.monitors["dp-2"].background, Some(MonitorBackground::Color(_))));
+    }
+
+    #[test]
+    fn relative_paths_are_resolved_from_base() {
+        assert_eq!(resolve_path(Path::new("/etc/sali"), &String::from("styles.css")), Path::new("/etc/sali/styles.css"));
+        assert_eq!(resolve_path(Path::new("/etc/sali"), &String::from("/usr/share/sali.css")), Path::new("/usr/share/sali.css"));
+    }
+
+    #[test]
+    fn attempt_delay_doubles_up_to_maximum() {
+        let policy = AttemptPolicy { delay: 1000, max_delay: 5000, ..Default::default() };
+        let delays = (0..6).map(|failures| policy.delay_after(failures).as_millis()).collect::<Vec<_>>();
+        assert_eq!(delays, [0, 1000, 2000, 4000, 5000, 5000]);
+        assert_eq!(policy.delay_after(u32::MAX), Duration::from_millis(5000));
+        assert_eq!(AttemptPolicy::default().delay_after(3), Duration::ZERO);
+    }
+
+    fn color(yaml: &str) -> Result<Color, serde_yaml::Error> {
+        serde_yaml::from_str(yaml)
+    }
+
+    #[test]
+    fn hex_colors() {
+        let short = color("\"#f80\"").unwrap();
+        assert_eq!((short.r, short.g, short.b, short.a), (255, 136, 0, 1.0));
+        let long = color("\"#2e3440\"").unwrap();
+        assert_eq!((long.r, long.g, long.b, long.a), (46, 52, 64, 1.0));
+        let alpha = color("\"#2E344080\"").unwrap();
+        assert_eq!((alpha.r, alpha.g, alpha.b), (46, 52, 64));
+        assert!((alpha.a - 128.0 / 255.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn rgb_and_rgba_colors() {
+        let rgb = color("[46, 52, 64]").unwrap();
+        assert_eq!((rgb.r, rgb.g, rgb.b, rgb.a), (46, 52, 64, 1.0));
+        let rgba = color("[46, 52, 64, 0.5]").unwrap();
+        assert_eq!((rgba.r, rgba.g, rgba.b, rgba.a), (46, 52, 64, 0.5));
+        assert_eq!(color("[0, 0, 0, 2.5]").unwrap().a, 1.0);
+        assert_eq!(rgba.css(), "rgba(46,52,64,0.5)");
+    }
+
+    #[test]
+    fn invalid_colors() {
+        for invalid in ["\"2e3440\"", "\"#2e344\"", "\"#ggg\"", "\"#\"", "\"red\"", "[256, 0, 0]", "[0, 0]", "[0, 0, 0, 0, 0]"] {
+            assert!(color(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn substitute_keeps_escaped_variables() {
+        assert_eq!(substitute("echo $${HOME}"), "echo ${HOME}");
+        assert_eq!(substitute("$${A} $${B}"), "${A} ${B}");
+    }
+
+    #[test]
+    fn substitute_uses_default_for_unset_and_empty_variables() {
+        std::env::remove_var("SALI_TEST_UNSET");
+        std::env::set_var("SALI_TEST_EMPTY", "");
+        std::env::set_var("SALI_TEST_SET", "value");
+        assert_eq!(substitute("${SALI_TEST_UNSET:-default}"), "default");
+        assert_eq!(substitute("${SALI_TEST_EMPTY:-default}"), "default");
+        assert_eq!(substitute("${SALI_TEST_SET:-default}"), "value");
+        assert_eq!(substitute("a${SALI_TEST_UNSET}b"), "ab");
+    }
+
+    #[test]
+    fn substitute_keeps_unterminated_variables() {
+        assert_eq!(substitute("prefix ${HOME"), "prefix ${HOME");
+        std::env::set_var("SALI_TEST_TERMINATED", "value");
+        assert_eq!(substitute("${SALI_TEST_TERMINATED} ${"), "value ${");
+    }
+}
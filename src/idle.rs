@@ -0,0 +1,104 @@
+use std::{cell::Cell, rc::Rc, sync::Arc, time::{Duration, Instant}};
+
+use glib::{timeout_add_local, ControlFlow};
+use gtk4 as gtk;
+use gtk::gdk::EventType;
+use gtk::{*, prelude::*};
+use log::{info, warn};
+
+use crate::actions::{focus_form, run_command};
+use crate::components::build::{build_component_tree, FormFields};
+use crate::components::condition::watch_conditions;
+use crate::config::Config;
+
+/// Watch the input on all windows and switch the greeter into the idle
+/// mode after the configured timeout without any input
+pub fn watch_idle(window: &ApplicationWindow, form: &Widget, fields: &FormFields, config: &Arc<Config>) {
+    let Some(idle) = &config.idle else {
+        return;
+    };
+
+    let idle_tree = idle.layout.clone().and_then(|layout| {
+        let mut idle_fields = FormFields::default();
+        let tree = build_component_tree(layout, &mut idle_fields, config);
+        if idle_fields.username.is_some() || idle_fields.password.is_some() || idle_fields.runner.is_some() {
+            warn!("form fields in the idle layout can't be used for login");
+        }
+        // live conditions of the idle layout follow the fields of the login form
+        let conditions = FormFields { conditions: idle_fields.conditions, ..fields.clone() };
+        watch_conditions(&conditions, config);
+        tree.map(|tree| tree.as_ref().borrow().clone())
+    });
+
+    let last_input = Rc::new(Cell::new(Instant::now()));
+    let is_idle = Rc::new(Cell::new(false));
+
+    let (cl, ci, cw, cf, cc, cd) = (last_input.clone(), is_idle.clone(), window.clone(), form.clone(), config.clone(), fields.clone());
+    let on_input: Rc<dyn Fn() -> glib::Propagation> = Rc::new(move || {
+        cl.set(Instant::now());
+        if !ci.replace(false) {
+            return glib::Propagation::Proceed;
+        }
+
+        info!("waking up from idle mode");
+        Window::list_toplevels().iter().for_each(|window| window.remove_css_class(&cc.classes.idle));
+        if cw.child().as_ref() != Some(&cf) {
+            cw.set_child(Some(&cf));
+        }
+        if let Some(cmd) = cc.idle.as_ref().and_then(|idle| idle.wake_cmd.as_ref()) {
+            run_command(cmd);
+        }
+        focus_form(&cd);
+        // the input which woke up the greeter shouldn't end up in the form
+        glib::Propagation::Stop
+    });
+
+    // the background windows on the other monitors may be opened after the form window
+    let toplevels = Window::toplevels();
+    (0..toplevels.n_items())
+        .filter_map(|i| toplevels.item(i).and_downcast::<Window>())
+        .for_each(|window| add_input_controller(&window, &on_input));
+    toplevels.connect_items_changed(move |toplevels, position, _, added| {
+        (position..position + added)
+            .filter_map(|i| toplevels.item(i).and_downcast::<Window>())
+            .for_each(|window| add_input_controller(&window, &on_input));
+    });
+
+    let (cw, cc) = (window.clone(), config.clone());
+    let timeout = Duration::from_secs(idle.timeout);
+    timeout_add_local(Duration::from_secs(1), move || {
+        if is_idle.get() || last_input.get().elapsed() < timeout {
+            return ControlFlow::Continue;
+        }
+
+        info!("entering idle mode");
+        is_idle.set(true);
+        Window::list_toplevels().iter().for_each(|window| window.add_css_class(&cc.classes.idle));
+        if let Some(tree) = &idle_tree {
+            cw.set_child(Some(tree));
+        }
+        if let Some(cmd) = cc.idle.as_ref().and_then(|idle| idle.cmd.as_ref()) {
+            run_command(cmd);
+        }
+        ControlFlow::Continue
+    });
+}
+
+/// Call the input handler for every key, pointer and touch input on the window
+fn add_input_controller(window: &Window, on_input: &Rc<dyn Fn() -> glib::Propagation>) {
+    let controller = EventControllerLegacy::new();
+    controller.set_propagation_phase(PropagationPhase::Capture);
+    let co = on_input.clone();
+    controller.connect_event(move |_, event| {
+        let is_input = matches!(
+            event.event_type(),
+            EventType::KeyPress | EventType::ButtonPress | EventType::MotionNotify | EventType::Scroll | EventType::TouchBegin
+        );
+        if is_input {
+            co()
+        } else {
+            glib::Propagation::Proceed
+        }
+    });
+    window.add_controller(controller);
+}
@@ -1,11 +1,37 @@
 use greetd_ipc::codec::SyncCodec;
 use greetd_ipc::{AuthMessageType, ErrorType, Request, Response};
 use log::{error, info, warn};
-use std::env;
+use std::{env, fmt, io::{self, Write}};
 use std::os::unix::net::UnixStream;
+use zeroize::{Zeroize, Zeroizing};
 
 use crate::config::Runner;
 
+/// Credential which is wiped from memory once it's dropped
+/// and which is never included in any debug or log output
+pub struct Secret(Zeroizing<String>);
+
+impl Secret {
+    pub fn new(value: String) -> Self {
+        Self(Zeroizing::new(value))
+    }
+
+    /// Access the plain value of the secret
+    pub fn expose(&self) -> &str {
+        self.0.as_str()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Secret(***)")
+    }
+}
+
 pub enum LoginFailure {
     /// The login attempt failed due to invalid auth credentials
     AuthError,
@@ -24,8 +50,9 @@ pub enum LoginResult {
 
 pub fn handle_login(
     username: String,
-    password: String,
-    runner: &Runner
+    password: Secret,
+    runner: &Runner,
+    env: Vec<String>
 ) -> LoginResult {
     if username.is_empty() || password.is_empty() {
         return LoginResult::Failure(LoginFailure::MissingFields);
@@ -47,7 +74,12 @@ pub fn handle_login(
     let mut next_request = Request::CreateSession { username };
 
     loop {
-        if let Err(err) = next_request.write_to(&mut stream) {
+        let written = write_request(&mut stream, &next_request);
+        // the response may contain a copy of the password which has to be wiped once it's sent
+        if let Request::PostAuthMessageResponse { response: Some(response) } = &mut next_request {
+            response.zeroize();
+        }
+        if let Err(err) = written {
             error!("unable to write to greetd socket: {err}");
             return LoginResult::Failure(LoginFailure::Error)
         };
@@ -66,7 +98,7 @@ pub fn handle_login(
                 auth_message_type,
             } => {
                 let response = match auth_message_type {
-                    AuthMessageType::Visible | AuthMessageType::Secret => Some(password.clone()),
+                    AuthMessageType::Visible | AuthMessageType::Secret => Some(password.expose().to_string()),
                     AuthMessageType::Info => {
                         info!("auth message info: {auth_message}");
                         None
@@ -85,13 +117,13 @@ pub fn handle_login(
                 } else {
                     starting = true;
                     next_request = Request::StartSession {
-                        env: runner.env.clone(),
+                        env: runner.env.iter().cloned().chain(env.iter().cloned()).collect(),
                         cmd: vec![runner.run.clone()],
                     }
                 }
             }
             Response::Error { error_type, description } => {
-                if let Err(err) = Request::CancelSession.write_to(&mut stream) {
+                if let Err(err) = write_request(&mut stream, &Request::CancelSession) {
                     error!("unable to close greetd session: {err}");
                     return LoginResult::Failure(LoginFailure::Error)
                 }
@@ -106,3 +138,52 @@ pub fn handle_login(
         }
     }
 }
+
+/// Write a request to the greetd socket using the same framing as greetd_ipc,
+/// a native endian length followed by the json body. In contrast to
+/// `Request::write_to` the serialized request is wiped from memory once it's
+/// sent, since it may contain the password
+fn write_request(stream: &mut UnixStream, request: &Request) -> io::Result<()> {
+    let mut counter = ByteCounter(0);
+    serde_json::to_writer(&mut counter, request)?;
+
+    // the buffer is allocated once, as growing it would leave copies behind
+    let mut message = Zeroizing::new(Vec::with_capacity(4 + counter.0));
+    message.extend_from_slice(&(counter.0 as u32).to_ne_bytes());
+    serde_json::to_writer(&mut *message, request)?;
+    stream.write_all(&message)
+}
+
+/// Writer which only counts the bytes written to it
+struct ByteCounter(usize);
+
+impl Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secret_is_not_debug_printed() {
+        let secret = Secret::new(String::from("hunter2"));
+        assert!(!format!("{secret:?}").contains("hunter2"));
+        assert_eq!(secret.expose(), "hunter2");
+    }
+
+    #[test]
+    fn counter_matches_serialized_length() {
+        let request = Request::PostAuthMessageResponse { response: Some(String::from("pass\"word")) };
+        let mut counter = ByteCounter(0);
+        serde_json::to_writer(&mut counter, &request).unwrap();
+        assert_eq!(counter.0, serde_json::to_vec(&request).unwrap().len());
+    }
+}
@@ -1,23 +1,33 @@
-use components::build::{build_component_tree, Wrapped};
+use attempts::Attempts;
+use components::build::{build_component_tree, FormFields, Wrapped};
 use gtk4 as gtk;
-use login::handle_login;
+use login::{handle_login, Secret};
+use std::ffi::CStr;
 use std::os::unix::process::CommandExt;
 use std::process;
-use std::{fs, sync::Arc};
+use std::time::{Duration, Instant};
+use std::{cell::{Cell, RefCell}, fs, rc::Rc, sync::Arc};
 
 use clap::Parser;
 use cli::Cli;
 use config::{Classes, Config};
 use gtk::gdk::{*, prelude::*};
 use gtk::{*, prelude::*};
+use gtk::glib::translate::ToGlibPtr;
 use gtk4_layer_shell::*;
 use log::{error, info, warn};
 use rsass::{compile_scss, output};
 
+mod actions;
+mod attempts;
+mod background;
 mod config;
+mod idle;
 mod cli;
+mod commands;
 mod components;
 mod login;
+mod templates;
 
 // TODO: Maybe cache the compiled styles using a checksum to prevent it from compiling in every run
 //       This could potentially mean a faster greeter 👀
@@ -27,6 +37,9 @@ const APP_ID: &str = "ch.wysbd.sali";
 fn main() {
        env_logger::init_from_env(env_logger::Env::new().default_filter_or("info"));
        let cli = Cli::parse();
+       if let Some(command) = cli.command {
+           process::exit(commands::run(command, &cli.config));
+       }
        if cli.inspect {
            info!("started with gtk inspector");
            std::env::set_var("GTK_DEBUG", "interactive")
@@ -127,6 +140,8 @@ fn build_background_window(app: &Application, monitor: &config::Monitor, config:
         .default_height(geometry.height())
         .build();
 
+    add_opening_class(&window, &config);
+
     window.init_layer_shell();
     window.set_layer(Layer::Background);
     window.set_monitor(&gdk_monitor);
@@ -136,14 +151,30 @@ fn build_background_window(app: &Application, monitor: &config::Monitor, config:
 
     let display = Display::default().expect("should have display");
     let class_name = format!("{}-{}", APP_ID.replace(".", "-"), monitor.output);
-    let class_content = match background {
-        config::MonitorBackground::Rgb(r, g, b) => format!("background-color: rgb({r},{g},{b})"),
-        config::MonitorBackground::Image(path) => format!("background: url(\"file://{}\")", config.resolve_path(path).to_string_lossy()),
+    let Some(background_css) = background::build_background(&class_name, monitor, &config) else {
+        return;
     };
-    let css_str = format!(r".{class_name} {{ {class_content}; background-size: cover; background-position: center; }}");
-    provider.load_from_data(&css_str);
+    provider.load_from_data(&background_css.css);
     window.add_css_class(&class_name);
 
+    if let Some(first) = background_css.classes.first() {
+        window.add_css_class(first);
+    }
+    let interval = match background {
+        config::MonitorBackground::Directory(directory) => directory.interval.filter(|_| background_css.classes.len() > 1),
+        _ => None
+    };
+    if let Some(interval) = interval {
+        let (cw, current) = (window.clone(), Cell::new(0));
+        glib::timeout_add_local(Duration::from_millis(interval), move || {
+            let next = (current.get() + 1) % background_css.classes.len();
+            cw.remove_css_class(&background_css.classes[current.get()]);
+            cw.add_css_class(&background_css.classes[next]);
+            current.set(next);
+            glib::ControlFlow::Continue
+        });
+    }
+
     gtk::style_context_add_provider_for_display(
         &display,
         &provider,
@@ -174,6 +205,8 @@ fn build_form_window(app: &Application, monitor: &config::Monitor, config: Arc<C
         .height_request(geometry.height())
         .build();
 
+    add_opening_class(&window, &config);
+
     window.init_layer_shell();
     window.set_anchor(Edge::Left, true);
     window.set_anchor(Edge::Top, true);
@@ -182,61 +215,72 @@ fn build_form_window(app: &Application, monitor: &config::Monitor, config: Arc<C
     window.set_monitor(&gdk_monitor);
     window.set_keyboard_mode(KeyboardMode::Exclusive);
 
-    let (mut username, mut password, mut runner) = (None, None, None);
+    let mut fields = FormFields::default();
 
     let tree = build_component_tree(
         config.layout.clone(),
-        &mut username,
-        &mut password,
-        &mut runner,
+        &mut fields,
         &config
     );
 
-    let Some(password) = password else {
+    let Some(password) = fields.password.clone() else {
         error!("no password component is specified");
         std::process::exit(1);
     };
 
-    if runner.is_none() && config.default_runner.is_none() {
+    if fields.runner.is_none() && config.default_runner.is_none() {
         error!("neither a runner component nor a default runner is specified");
         std::process::exit(1);
-    } else if username.is_none() && config.username.is_none() {
+    } else if fields.username.is_none() && config.username.is_none() {
         error!("neither a username component nor a default username is specified");
         std::process::exit(1)
     }
 
-    let add_empty_class = move |entry: &Entry, classes: &Classes| {
-        if entry.text().is_empty() {
-            entry.add_css_class(&classes.field_empty)
-        } else {
-            entry.remove_css_class(&classes.field_empty)
-        }
-    };
+    let attempts: Wrapped<Attempts> = Rc::new(RefCell::new(Attempts::default()));
 
-    let (cu, cr, cp, cc) = (username.clone(), runner.clone(), password.clone(), config.clone());
+    let (cf, ca, cc) = (fields.clone(), attempts.clone(), config.clone());
     let tmp = password.as_ref().borrow();
-    let entry = tmp.downcast_ref::<Entry>().expect("should be entry");
+    let entry = tmp.downcast_ref::<PasswordEntry>().expect("should be password entry");
     entry.connect_text_notify(move |entry| add_empty_class(entry, &cc.classes));
     let cc = config.clone();
+    entry.connect_changed(move |entry| entry.remove_css_class(&cc.classes.field_error));
+    let cc = config.clone();
     entry.connect_activate(move |_| {
-        handle_submit(cu.clone(), cp.clone(), cr.clone(), cc.clone());
+        handle_submit(&cf, &ca, cc.clone());
     });
 
-    let (cu, cr, cp, cc) = (username.clone(), runner.clone(), password.clone(), config.clone());
-    if let Some(usr) = username.clone() {
+    add_caps_lock_class(entry, &config);
+
+    let (cf, ca, cc) = (fields.clone(), attempts.clone(), config.clone());
+    if let Some(usr) = fields.username.clone() {
         let tmp = usr.as_ref().borrow();
         let entry = tmp.downcast_ref::<Entry>().expect("should be entry");
         entry.connect_text_notify(move |entry| add_empty_class(entry, &cc.classes));
         let cc = config.clone();
+        entry.connect_changed(move |entry| entry.remove_css_class(&cc.classes.field_error));
+        let cc = config.clone();
         entry.connect_activate(move |_| {
-            handle_submit(cu.clone(), cp.clone(), cr.clone(), cc.clone());
+            handle_submit(&cf, &ca, cc.clone());
         });
     }
 
+    actions::add_keybindings(&window, &fields, &attempts, &config);
+    components::condition::watch_conditions(&fields, &config);
+
+    if let Some(cmd) = &config.numlock_cmd {
+        info!("turning on num lock");
+        actions::run_command(cmd);
+    }
+
+    if let Some(layout) = config.default_keyboard_layout.as_ref().and_then(|name| config.keyboard_layouts.get(name)) {
+        actions::switch_keyboard_layout(layout);
+    }
+
     match tree {
         Some(child) => {
             let widget = child.as_ref().borrow();
             window.set_child(Some(widget.as_ref() as &Widget));
+            idle::watch_idle(&window, &widget, &fields, &config);
             window.present();
             if config.username.is_some() {
                 entry.grab_focus();
@@ -250,8 +294,57 @@ fn build_form_window(app: &Application, monitor: &config::Monitor, config: Arc<C
     }
 }
 
-fn handle_submit(username: Option<Wrapped<Widget>>, password: Wrapped<Widget>, runner: Option<Wrapped<Widget>>, config: Arc<Config>) {
-    let runner_opt = if let Some(runner) = runner {
+fn add_empty_class(entry: &(impl IsA<Editable> + IsA<Widget>), classes: &Classes) {
+    if is_text_empty(entry) {
+        entry.add_css_class(&classes.field_empty)
+    } else {
+        entry.remove_css_class(&classes.field_empty)
+    }
+}
+
+/// Toggle the caps lock class on the password field whenever
+/// the caps lock state of the keyboard changes
+fn add_caps_lock_class(entry: &PasswordEntry, config: &Arc<Config>) {
+    let Some(keyboard) = Display::default().and_then(|d| d.default_seat()).and_then(|s| s.keyboard()) else {
+        warn!("found no keyboard device to watch caps lock state");
+        return;
+    };
+
+    let toggle_class = |entry: &PasswordEntry, active: bool, class: &str| {
+        if active {
+            entry.add_css_class(class)
+        } else {
+            entry.remove_css_class(class)
+        }
+    };
+
+    toggle_class(entry, keyboard.is_caps_locked(), &config.classes.caps_lock);
+    let (ce, cc) = (entry.clone(), config.clone());
+    keyboard.connect_caps_lock_state_notify(move |device| {
+        toggle_class(&ce, device.is_caps_locked(), &cc.classes.caps_lock)
+    });
+}
+
+/// Boolean whether the text of the field is empty. `Editable::text` isn't used
+/// as it would create an unwiped copy of the password on every keystroke
+fn is_text_empty(editable: &impl IsA<Editable>) -> bool {
+    // SAFETY: the text is owned by the entry buffer and only read while it's valid
+    unsafe {
+        let text = gtk::ffi::gtk_editable_get_text(editable.upcast_ref::<Editable>().to_glib_none().0);
+        text.is_null() || *text == 0
+    }
+}
+
+/// Copy the password out of the entry buffer. `PasswordEntry::text` would
+/// return a `GString` copy of the password which is freed without being wiped
+fn password_text(entry: &PasswordEntry) -> Secret {
+    // SAFETY: the text is owned by the entry buffer and stays valid until the buffer changes
+    let text = unsafe { CStr::from_ptr(gtk::ffi::gtk_editable_get_text(entry.upcast_ref::<Editable>().to_glib_none().0)) };
+    Secret::new(text.to_string_lossy().into_owned())
+}
+
+fn handle_submit(fields: &FormFields, attempts: &Wrapped<Attempts>, config: Arc<Config>) {
+    let runner_opt = if let Some(runner) = &fields.runner {
         let tmp = runner.as_ref().borrow();
         let entry = tmp.downcast_ref::<DropDown>().expect("should be dropdown");
 
@@ -268,53 +361,145 @@ fn handle_submit(username: Option<Wrapped<Widget>>, password: Wrapped<Widget>, r
         return;
     };
 
+    let Some(password) = &fields.password else {
+        warn!("no password field found for submission");
+        return;
+    };
+
     let tmp = password.as_ref().borrow();
-    let password_entry = tmp.clone().downcast::<Entry>().expect("should be entry");
-    let username_entry = username.map(|usr| {
+    let password_entry = tmp.clone().downcast::<PasswordEntry>().expect("should be password entry");
+    let username_entry = fields.username.as_ref().map(|usr| {
         let tmp = usr.as_ref().borrow();
         tmp.clone().downcast::<Entry>().expect("should be entry")
     });
 
-    let password_str = password_entry.text().to_string();
     let username_str = match &username_entry {
         Some(entry) => entry.text().to_string(),
         None => config.username.clone().expect("should have default username")
     };
 
-    match handle_login(username_str, password_str, runner) {
+    let block = attempts.borrow().block(&username_str);
+    if let Some(block) = block {
+        warn!("login attempts for {username_str} are throttled");
+        attempts::show_countdown(block, attempts, fields, &config);
+        return;
+    }
+
+    let password_secret = password_text(&password_entry);
+    let password_missing = password_secret.is_empty();
+    // the entry buffer should not keep the password around after the attempt
+    password_entry.set_text("");
+
+    let keyboard_layout = match &fields.keyboard_layout {
+        Some(keyboard_layout) => {
+            let tmp = keyboard_layout.as_ref().borrow();
+            let dropdown = tmp.downcast_ref::<DropDown>().expect("should be dropdown");
+            dropdown.selected_item().and_downcast::<StringObject>().and_then(|selected| {
+                config.keyboard_layouts.values().find(|l| l.display_name == selected.string())
+            })
+        },
+        None => config.default_keyboard_layout.as_ref().and_then(|name| config.keyboard_layouts.get(name))
+    };
+    let env = keyboard_layout.map(|layout| layout.env()).unwrap_or_default();
+
+    match handle_login(username_str.clone(), password_secret, runner, env) {
         login::LoginResult::Failure(failure) => {
+            let auth_failed = !matches!(failure, login::LoginFailure::MissingFields);
+            if let Some(entry) = username_entry.as_ref().filter(|_| auth_failed && fields.clear_username_on_error) {
+                entry.set_text(&config.username.clone().unwrap_or_default());
+            }
+
             match failure {
                 login::LoginFailure::MissingFields => {
-                    if password_entry.text().is_empty() {
+                    if password_missing {
                         password_entry.add_css_class(&config.classes.field_error)
                     } else if let Some(entry) = &username_entry {
                         entry.add_css_class(&config.classes.field_error)
                     }
                 },
-                login::LoginFailure::AuthError |
+                login::LoginFailure::AuthError => {
+                    password_entry.add_css_class(&config.classes.field_error);
+                    if let Some(entry) = &username_entry { entry.add_css_class(&config.classes.field_error) };
+                    let block = attempts.borrow_mut().record_failure(&username_str, &config);
+                    if let Some(block) = block {
+                        attempts::show_countdown(block, attempts, fields, &config);
+                    }
+                },
                 login::LoginFailure::Error => {
                     password_entry.add_css_class(&config.classes.field_error);
                     if let Some(entry) = &username_entry { entry.add_css_class(&config.classes.field_error) };
                 },
             }
+
+            actions::focus_form(fields);
+
+            if config.error_timeout > 0 {
+                // a previous failure shouldn't remove the error class of this one early
+                if let Some(source) = fields.error_timeout.borrow_mut().take() {
+                    source.remove();
+                }
+                let (cp, cu, cc) = (password_entry.clone(), username_entry.clone(), config.clone());
+                let ct = fields.error_timeout.clone();
+                let source = glib::timeout_add_local_once(Duration::from_millis(config.error_timeout), move || {
+                    ct.borrow_mut().take();
+                    cp.remove_css_class(&cc.classes.field_error);
+                    if let Some(entry) = &cu { entry.remove_css_class(&cc.classes.field_error) };
+                });
+                fields.error_timeout.replace(Some(source));
+            }
         },
         login::LoginResult::Success => {
             info!("login attempt succeeded");
-            if let Some(cmd) = &runner.exit_cmd {
-                if let Some(argv) = shlex::split(&cmd) {
-                    let mut command = std::process::Command::new(&argv[0]);
-                    command.args(&argv[1..]);
-                    info!("using custom exit command: {command:?}");
-                    let err = command.exec();
-                    error!("unable to exit with custom exit command: {err}");
-                    std::process::exit(0);
-                } else {
-                    error!("received invalid custom exit command");
-                    std::process::exit(0);
-                }
+            if let Some(root) = password_entry.root() {
+                root.set_sensitive(false);
+            }
+            Window::list_toplevels().iter().for_each(|window| window.add_css_class(&config.classes.closing));
+
+            let exit_cmd = runner.exit_cmd.clone();
+            if config.exit_delay > 0 {
+                glib::timeout_add_local_once(Duration::from_millis(config.exit_delay), move || exit_greeter(exit_cmd));
             } else {
-                std::process::exit(0);
+                exit_greeter(exit_cmd);
             }
         },
     }
-}
\ No newline at end of file
+}
+
+fn exit_greeter(exit_cmd: Option<String>) {
+    if let Some(cmd) = &exit_cmd {
+        if let Some(argv) = shlex::split(cmd) {
+            let mut command = std::process::Command::new(&argv[0]);
+            command.args(&argv[1..]);
+            info!("using custom exit command: {command:?}");
+            let err = command.exec();
+            error!("unable to exit with custom exit command: {err}");
+            std::process::exit(0);
+        } else {
+            error!("received invalid custom exit command");
+            std::process::exit(0);
+        }
+    } else {
+        std::process::exit(0);
+    }
+}
+
+/// Apply the opening class to the window until it was drawn at least once
+/// and the opening duration has passed
+fn add_opening_class(window: &ApplicationWindow, config: &Arc<Config>) {
+    window.add_css_class(&config.classes.opening);
+
+    let cc = config.clone();
+    window.connect_map(move |window| {
+        let (mapped, frames, cc) = (Instant::now(), Cell::new(0), cc.clone());
+        let duration = Duration::from_millis(cc.opening_duration);
+        window.add_tick_callback(move |window, _| {
+            frames.set(frames.get() + 1);
+            // the class has to be present for at least one drawn frame for css transitions to start
+            if frames.get() < 2 || mapped.elapsed() < duration {
+                return glib::ControlFlow::Continue;
+            }
+            window.remove_css_class(&cc.classes.opening);
+            glib::ControlFlow::Break
+        });
+    });
+}
@@ -0,0 +1,278 @@
+use log::warn;
+use serde_yaml::{Mapping, Value};
+
+use crate::config::merge_values;
+
+/// Key of the config which holds the named templates
+const TEMPLATES_KEY: &str = "templates";
+/// Component type of nodes which instantiate a template
+const USE_TYPE: &str = "use";
+const TYPE_KEY: &str = "type";
+const TEMPLATE_KEY: &str = "template";
+/// Key of the parameters on a use node and of their default values on a template
+const PARAMS_KEY: &str = "params";
+
+/// Remove the templates from the config and replace all nodes
+/// with `type: use` by an instance of the referenced template
+pub fn expand_templates(value: &mut Value) -> Result<(), String> {
+    let templates = match value.as_mapping_mut().and_then(|mapping| mapping.remove(TEMPLATES_KEY)) {
+        None => Mapping::new(),
+        Some(Value::Mapping(templates)) => templates,
+        Some(_) => return Err(String::from("templates have to be a map of named components"))
+    };
+    expand(value, &templates, &mut Vec::new())
+}
+
+fn expand(value: &mut Value, templates: &Mapping, stack: &mut Vec<String>) -> Result<(), String> {
+    match value {
+        Value::Mapping(mapping) if is_use(mapping) => {
+            let instance = instantiate(mapping, templates, stack)?;
+            *value = instance;
+        },
+        Value::Mapping(mapping) => {
+            for child in mapping.values_mut() {
+                expand(child, templates, stack)?;
+            }
+        },
+        Value::Sequence(sequence) => {
+            for child in sequence.iter_mut() {
+                expand(child, templates, stack)?;
+            }
+        },
+        Value::Tagged(tagged) => expand(&mut tagged.value, templates, stack)?,
+        _ => {}
+    }
+    Ok(())
+}
+
+fn is_use(mapping: &Mapping) -> bool {
+    mapping.get(TYPE_KEY).and_then(Value::as_str) == Some(USE_TYPE)
+}
+
+/// Build the component of the template referenced by the use node. The parameters
+/// are substituted first and all other attributes of the use node are merged on top
+fn instantiate(node: &Mapping, templates: &Mapping, stack: &mut Vec<String>) -> Result<Value, String> {
+    let Some(name) = node.get(TEMPLATE_KEY).and_then(Value::as_str) else {
+        return Err(String::from("component with type use is missing the template name"));
+    };
+    if stack.iter().any(|used| used == name) {
+        return Err(format!("template {name} uses itself"));
+    }
+    let Some(template) = templates.get(name) else {
+        return Err(format!("no template named {name} found"));
+    };
+
+    let mut instance = template.clone();
+    let mut params = match instance.as_mapping_mut().and_then(|mapping| mapping.remove(PARAMS_KEY)) {
+        None => Mapping::new(),
+        Some(Value::Mapping(defaults)) => defaults,
+        Some(_) => return Err(format!("params of template {name} have to be a map"))
+    };
+    match node.get(PARAMS_KEY) {
+        None => {},
+        Some(Value::Mapping(values)) => params.extend(values.clone()),
+        Some(_) => return Err(format!("params for template {name} have to be a map"))
+    }
+    substitute_params(&mut instance, &params, name);
+
+    let overrides = node.iter()
+        .filter(|(key, _)| !matches!(key.as_str(), Some(TYPE_KEY | TEMPLATE_KEY | PARAMS_KEY)))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect::<Mapping>();
+    merge_values(&mut instance, Value::Mapping(overrides));
+
+    stack.push(name.to_string());
+    expand(&mut instance, templates, stack)?;
+    stack.pop();
+    Ok(instance)
+}
+
+/// Replace all `{{param}}` placeholders in the strings of the value. A string which
+/// only consists of a placeholder is replaced by the parameter value itself to keep its type
+fn substitute_params(value: &mut Value, params: &Mapping, template: &str) {
+    match value {
+        Value::String(str) => {
+            let whole = str.trim()
+                .strip_prefix("{{")
+                .and_then(|rest| rest.strip_suffix("}}"))
+                .filter(|param| !param.contains("{{") && !param.contains("}}"))
+                .and_then(|param| params.get(param.trim()));
+            match whole {
+                Some(param) => *value = param.clone(),
+                None => *str = substitute_string(str, params, template)
+            }
+        },
+        Value::Sequence(sequence) => sequence.iter_mut().for_each(|child| substitute_params(child, params, template)),
+        Value::Mapping(mapping) => mapping.values_mut().for_each(|child| substitute_params(child, params, template)),
+        Value::Tagged(tagged) => substitute_params(&mut tagged.value, params, template),
+        _ => {}
+    }
+}
+
+fn substitute_string(str: &str, params: &Mapping, template: &str) -> String {
+    let mut result = String::with_capacity(str.len());
+    let mut rest = str;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}").map(|end| start + end) else {
+            break;
+        };
+        result.push_str(&rest[..start]);
+
+        let name = rest[start + 2..end].trim();
+        match params.get(name) {
+            Some(Value::String(param)) => result.push_str(param),
+            Some(Value::Number(param)) => result.push_str(&param.to_string()),
+            Some(Value::Bool(param)) => result.push_str(&param.to_string()),
+            Some(_) => {
+                warn!("parameter {name} of template {template} can't be inserted into a text");
+                result.push_str(&rest[start..end + 2]);
+            },
+            None => {
+                warn!("missing parameter {name} for template {template}");
+                result.push_str(&rest[start..end + 2]);
+            }
+        }
+        rest = &rest[end + 2..];
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expanded(yaml: &str) -> Result<Value, String> {
+        let mut value: Value = serde_yaml::from_str(yaml).unwrap();
+        expand_templates(&mut value).map(|_| value)
+    }
+
+    fn yaml(yaml: &str) -> Value {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn params_and_defaults() {
+        let value = expanded(r#"
+templates:
+  action:
+    params:
+      icon: "*"
+    type: button
+    label: "{{icon}} {{label}}"
+    command: "{{command}}"
+layout:
+  type: box
+  children:
+    - type: use
+      template: action
+      params: { label: Reboot, command: systemctl reboot }
+    - type: use
+      template: action
+      params: { icon: "!", label: Power off, command: systemctl poweroff }
+      classes: [ "danger" ]
+"#).unwrap();
+        assert_eq!(value, yaml(r#"
+layout:
+  type: box
+  children:
+    - type: button
+      label: "* Reboot"
+      command: systemctl reboot
+    - type: button
+      label: "! Power off"
+      command: systemctl poweroff
+      classes: [ "danger" ]
+"#));
+    }
+
+    #[test]
+    fn whole_and_inline_params() {
+        let value = expanded(r#"
+templates:
+  spaced:
+    type: box
+    spacing: "{{spacing}}"
+    classes: "{{classes}}"
+    children: [ "{{child}}" ]
+    name: "box-{{spacing}}"
+layout:
+  type: use
+  template: spaced
+  params:
+    spacing: 12
+    classes: [ "a", "b" ]
+    child: { type: label, label: Text }
+"#).unwrap();
+        assert_eq!(value, yaml(r#"
+layout:
+  type: box
+  spacing: 12
+  classes: [ "a", "b" ]
+  children: [ { type: label, label: Text } ]
+  name: box-12
+"#));
+    }
+
+    #[test]
+    fn missing_and_unsupported_params_are_kept() {
+        let value = expanded(r#"
+templates:
+  label:
+    type: label
+    label: "{{missing}} {{list}}"
+layout:
+  type: use
+  template: label
+  params: { list: [ 1, 2 ] }
+"#).unwrap();
+        assert_eq!(value, yaml("layout: { type: label, label: \"{{missing}} {{list}}\" }"));
+    }
+
+    #[test]
+    fn unknown_template() {
+        let err = expanded("layout: { type: use, template: missing }").unwrap_err();
+        assert!(err.contains("no template named missing"), "{err}");
+        assert!(expanded("layout: { type: use }").is_err());
+    }
+
+    #[test]
+    fn recursive_templates() {
+        let err = expanded(r#"
+templates:
+  outer:
+    type: box
+    children: [ { type: use, template: inner } ]
+  inner:
+    type: box
+    children: [ { type: use, template: outer } ]
+layout: { type: use, template: outer }
+"#).unwrap_err();
+        assert!(err.contains("uses itself"), "{err}");
+
+        let err = expanded(r#"
+templates:
+  own:
+    type: box
+    children: [ { type: use, template: own } ]
+layout: { type: use, template: own }
+"#).unwrap_err();
+        assert!(err.contains("template own uses itself"), "{err}");
+    }
+
+    #[test]
+    fn nested_templates_share_no_stack() {
+        // using the same template twice next to each other isn't recursion
+        let value = expanded(r#"
+templates:
+  text: { type: label, label: "{{text}}" }
+  pair:
+    type: box
+    children:
+      - { type: use, template: text, params: { text: a } }
+      - { type: use, template: text, params: { text: b } }
+layout: { type: use, template: pair }
+"#).unwrap();
+        assert_eq!(value, yaml("layout: { type: box, children: [ { type: label, label: a }, { type: label, label: b } ] }"));
+    }
+}